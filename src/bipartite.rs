@@ -0,0 +1,60 @@
+use crate::csr::CSR;
+
+// A bipartite graph with left nodes 0..n_left and right nodes 0..n_right.
+// `left` holds the (sorted) right neighbors of each left node and `right` the reverse.
+pub struct Bipartite{
+    pub left: CSR,
+    pub right: CSR
+}
+
+impl Bipartite{
+
+    // edges are (left node, right node) pairs without duplicates.
+    pub fn from_edges(edges: &[(usize, usize)], n_left: usize, n_right: usize) -> Bipartite{
+        let mut sorted = edges.to_vec();
+        sorted.sort_unstable();
+        let left = CSR::from_sorted_edges(&sorted, n_left);
+
+        for e in sorted.iter_mut() {
+            *e = (e.1, e.0);
+        }
+        sorted.sort_unstable();
+        let right = CSR::from_sorted_edges(&sorted, n_right);
+
+        return Bipartite { left, right };
+    }
+
+    pub fn n_left(&self) -> usize{
+        return self.left.n_nodes();
+    }
+
+    pub fn n_right(&self) -> usize{
+        return self.right.n_nodes();
+    }
+
+    pub fn n_edges(&self) -> usize{
+        return self.left.edges.len();
+    }
+
+    // exchanges the two partitions.
+    pub fn transpose(self) -> Bipartite{
+        return Bipartite { left: self.right, right: self.left };
+    }
+
+    // maps each position in right.edges to the position of the same edge in left.edges.
+    pub fn right_to_left_positions(&self) -> Vec<usize>{
+        let mut ptrs = self.left.nodes[..self.n_left()].to_vec();
+        let mut positions = vec![0usize; self.right.edges.len()];
+
+        // right nodes are visited in increasing order, as stored in each left neighbor list
+        for v in 0..self.n_right() {
+            for q in self.right.nodes[v]..self.right.nodes[v+1] {
+                let u = self.right.edges[q];
+                positions[q] = ptrs[u];
+                ptrs[u] += 1;
+            }
+        }
+
+        return positions;
+    }
+}
@@ -0,0 +1,313 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crossbeam;
+
+use crate::bipartite::Bipartite;
+use crate::csr::CSR;
+use crate::rng::Rng;
+use crate::tricnt;
+
+// Butterfly (2x2 biclique) counting on bipartite graphs by wedge aggregation
+// (Wang et al., Sanei-Mehri et al.). A wedge u-v-w joins two nodes u, w of the same side
+// through a middle node v of the other side; c common middles of u and w make C(c, 2) butterflies.
+
+pub fn count(graph: &Bipartite) -> usize{
+    let (start, mid) = wedge_sides(graph);
+
+    let mut cnt = 0usize;
+    let mut common = vec![0usize; start.n_nodes()];
+    let mut touched = vec![];
+    for u in 0..start.n_nodes() {
+        cnt += count_from(u, start, mid, &mut common, &mut touched);
+    }
+    return cnt;
+}
+
+pub fn count_parallel(graph: &Bipartite, n_thread: usize) -> usize{
+    let n_thread = n_thread.max(1);
+    let (start, mid) = wedge_sides(graph);
+
+    let mut cnt = 0usize;
+
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for i in 0..n_thread{
+            threads.push(scope.spawn(move |_| -> usize {
+                let mut cnt = 0usize;
+                let mut common = vec![0usize; start.n_nodes()];
+                let mut touched = vec![];
+                for u in (i..start.n_nodes()).step_by(n_thread) {
+                    cnt += count_from(u, start, mid, &mut common, &mut touched);
+                }
+                return cnt;
+            }));
+        }
+
+        for t in threads {
+            cnt += t.join().unwrap();
+        }
+    }).unwrap();
+
+    return cnt;
+}
+
+// Starts wedges from the side that makes fewer of them, i.e. whose middles have smaller degrees.
+fn wedge_sides(graph: &Bipartite) -> (&CSR, &CSR){
+    let wedges = |g: &CSR| -> usize { (0..g.n_nodes()).map(|v| g.degree(v) * g.degree(v)).sum() };
+
+    if wedges(&graph.right) <= wedges(&graph.left) {
+        return (&graph.left, &graph.right);
+    }
+    return (&graph.right, &graph.left);
+}
+
+// butterflies made of u and a node w > u.
+fn count_from(u: usize, start: &CSR, mid: &CSR, common: &mut [usize], touched: &mut Vec<usize>) -> usize{
+    for v in start.neighbors(u) {
+        for w in mid.neighbors(*v) {
+            if *w > u {
+                if common[*w] == 0 {
+                    touched.push(*w);
+                }
+                common[*w] += 1;
+            }
+        }
+    }
+
+    let mut cnt = 0usize;
+    for w in touched.drain(..) {
+        cnt += common[w] * (common[w] - 1) / 2;
+        common[w] = 0;
+    }
+    return cnt;
+}
+
+// Number of butterflies containing each left and each right node.
+pub fn count_per_vertex(graph: &Bipartite) -> (Vec<usize>, Vec<usize>){
+    let (start, mid) = (&graph.left, &graph.right);
+
+    let mut left_cnt = vec![0usize; graph.n_left()];
+    let mut right_cnt = vec![0usize; graph.n_right()];
+    let mut common = vec![0usize; graph.n_left()];
+    let mut touched = vec![];
+
+    for u in 0..graph.n_left() {
+        for v in start.neighbors(u) {
+            for w in mid.neighbors(*v) {
+                if *w > u {
+                    if common[*w] == 0 {
+                        touched.push(*w);
+                    }
+                    common[*w] += 1;
+                }
+            }
+        }
+
+        // each pair of common middles of u and w makes a butterfly
+        for v in start.neighbors(u) {
+            for w in mid.neighbors(*v) {
+                if *w > u && common[*w] > 1 {
+                    right_cnt[*v] += common[*w] - 1;
+                }
+            }
+        }
+
+        for w in touched.drain(..) {
+            let cnt = common[w] * (common[w] - 1) / 2;
+            left_cnt[u] += cnt;
+            left_cnt[w] += cnt;
+            common[w] = 0;
+        }
+    }
+
+    return (left_cnt, right_cnt);
+}
+
+// Number of butterflies containing each edge, aligned with graph.left.edges.
+pub fn count_per_edge(graph: &Bipartite) -> Vec<usize>{
+    let (start, mid) = (&graph.left, &graph.right);
+
+    let mut edge_cnt = vec![0usize; graph.n_edges()];
+    let mut common = vec![0usize; graph.n_left()];
+    let mut touched = vec![];
+
+    for u in 0..graph.n_left() {
+        for v in start.neighbors(u) {
+            for w in mid.neighbors(*v) {
+                if *w != u {
+                    if common[*w] == 0 {
+                        touched.push(*w);
+                    }
+                    common[*w] += 1;
+                }
+            }
+        }
+
+        for p in start.nodes[u]..start.nodes[u+1] {
+            for w in mid.neighbors(start.edges[p]) {
+                if *w != u {
+                    edge_cnt[p] += common[*w] - 1;
+                }
+            }
+        }
+
+        for w in touched.drain(..) {
+            common[w] = 0;
+        }
+    }
+
+    return edge_cnt;
+}
+
+// Butterflies containing the edge (u, v), from the common neighbors of u and each other end of v.
+fn count_edge(u: usize, v: usize, graph: &Bipartite) -> usize{
+    let mut cnt = 0usize;
+    for w in graph.right.neighbors(v) {
+        if *w != u {
+            cnt += tricnt::csr::count_intersect(u, *w, &graph.left) - 1;
+        }
+    }
+    return cnt;
+}
+
+// Estimates the number of butterflies from n_samples edges drawn uniformly with replacement.
+// Every butterfly has four edges, so m * (mean butterflies per sampled edge) / 4 is unbiased.
+pub fn count_approx(graph: &Bipartite, n_samples: usize, seed: u64) -> f64{
+    let n_edges = graph.n_edges();
+    if n_edges == 0 || n_samples == 0 {
+        return 0.0;
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut sum = 0usize;
+    for _ in 0..n_samples {
+        let p = rng.gen_range(n_edges);
+        let u = graph.left.nodes.partition_point(|x| *x <= p) - 1;
+        sum += count_edge(u, graph.left.edges[p], graph);
+    }
+
+    return sum as f64 * n_edges as f64 / (4 * n_samples) as f64;
+}
+
+// Butterflies containing the edge (u, v), counted through one wedge u-v-w drawn uniformly among
+// the deg(v) - 1 wedges at v: each of the c - 1 other common middles of u and w closes a butterfly.
+fn sample_wedge(u: usize, v: usize, start: &CSR, mid: &CSR, rng: &mut Rng) -> usize{
+    let others = mid.neighbors(v);
+    if others.len() < 2 {
+        return 0;
+    }
+
+    // u is swapped for the last neighbor
+    let mut w = others[rng.gen_range(others.len() - 1)];
+    if w == u {
+        w = others[others.len() - 1];
+    }
+    return (others.len() - 1) * (tricnt::csr::count_intersect(u, w, start) - 1);
+}
+
+// Estimates the number of butterflies containing each left and each right node from n_samples
+// wedges per node: an edge (u, v) drawn uniformly among those of u, then a wedge u-v-w.
+// Each butterfly of u contains two of its edges, so deg(u) / 2 times the mean is unbiased.
+pub fn count_per_vertex_approx(graph: &Bipartite, n_samples: usize, seed: u64) -> (Vec<f64>, Vec<f64>){
+    let mut rng = Rng::new(seed);
+    let mut estimate = |start: &CSR, mid: &CSR| -> Vec<f64> {
+        return (0..start.n_nodes()).map(|u| {
+            let neighbors = start.neighbors(u);
+            if neighbors.is_empty() || n_samples == 0 {
+                return 0.0;
+            }
+            let mut sum = 0usize;
+            for _ in 0..n_samples {
+                let v = neighbors[rng.gen_range(neighbors.len())];
+                sum += sample_wedge(u, v, start, mid, &mut rng);
+            }
+            sum as f64 * neighbors.len() as f64 / (2 * n_samples) as f64
+        }).collect();
+    };
+
+    let left_cnt = estimate(&graph.left, &graph.right);
+    let right_cnt = estimate(&graph.right, &graph.left);
+    return (left_cnt, right_cnt);
+}
+
+// Estimates the number of butterflies containing each edge from n_samples wedges per edge,
+// aligned with graph.left.edges.
+pub fn count_per_edge_approx(graph: &Bipartite, n_samples: usize, seed: u64) -> Vec<f64>{
+    let mut rng = Rng::new(seed);
+    let mut edge_cnt = vec![0.0; graph.n_edges()];
+    if n_samples == 0 {
+        return edge_cnt;
+    }
+
+    for u in 0..graph.n_left() {
+        for p in graph.left.nodes[u]..graph.left.nodes[u+1] {
+            let v = graph.left.edges[p];
+            let mut sum = 0usize;
+            for _ in 0..n_samples {
+                sum += sample_wedge(u, v, &graph.left, &graph.right, &mut rng);
+            }
+            edge_cnt[p] = sum as f64 / n_samples as f64;
+        }
+    }
+    return edge_cnt;
+}
+
+// Bitruss decomposition (Sariyüce & Pinar, Wang et al.): the bitruss number of an edge is the
+// largest k such that it belongs to a subgraph in which every edge is in at least k butterflies.
+// Returned values are aligned with graph.left.edges.
+pub fn bitruss(graph: &Bipartite) -> Vec<usize>{
+    let left = &graph.left;
+    let right = &graph.right;
+    let to_left = graph.right_to_left_positions();
+
+    let mut support = count_per_edge(graph);
+    let mut removed = vec![false; graph.n_edges()];
+    let mut bitruss = vec![0usize; graph.n_edges()];
+
+    let mut heap : BinaryHeap<Reverse<(usize, usize)>> = support.iter().enumerate().map(|(p, s)| Reverse((*s, p))).collect();
+    let mut level = 0usize;
+
+    // left node of each edge
+    let mut sources = vec![0usize; graph.n_edges()];
+    for u in 0..graph.n_left() {
+        for p in left.nodes[u]..left.nodes[u+1] {
+            sources[p] = u;
+        }
+    }
+
+    while let Some(Reverse((s, e))) = heap.pop() {
+        // stale entries are left behind when the support of an edge drops
+        if removed[e] || s != support[e] {
+            continue;
+        }
+
+        level = level.max(s);
+        bitruss[e] = level;
+        removed[e] = true;
+
+        // butterflies (u, v, w, x) with the edges (u, x), (w, v), (w, x)
+        let (u, v) = (sources[e], left.edges[e]);
+        for q in right.nodes[v]..right.nodes[v+1] {
+            let w = right.edges[q];
+            let e_wv = to_left[q];
+            if w == u || removed[e_wv] {
+                continue;
+            }
+
+            tricnt::csr::for_each_intersect(u, w, left, |pu, pw| {
+                if pu == e || removed[pu] || removed[pw] {
+                    return;
+                }
+                for f in [pu, e_wv, pw] {
+                    if support[f] > level {
+                        support[f] -= 1;
+                        heap.push(Reverse((support[f], f)));
+                    }
+                }
+            });
+        }
+    }
+
+    return bitruss;
+}
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use crossbeam;
+
+use crate::csr::CSR;
+use crate::rng::Rng;
+
+// Betweenness, closeness and harmonic centrality over an unweighted CSR holding the
+// out-neighbors of each node. Each measure runs a BFS per source, sources being split over threads.
+// With `Sources::Pivots`, only a uniform sample of sources is used and the accumulated values
+// are scaled by n / n_pivots to estimate the exact ones (Brandes & Pich, Eppstein & Wang).
+
+pub enum Sources{
+    All,
+    Pivots{ n_pivots: usize, seed: u64 }
+}
+
+impl Sources{
+
+    fn select(&self, n_nodes: usize) -> Vec<usize>{
+        match self {
+            Sources::All => (0..n_nodes).collect(),
+            Sources::Pivots{ n_pivots, seed } => {
+                // partial Fisher–Yates shuffle
+                let k = (*n_pivots).min(n_nodes);
+                let mut nodes : Vec<usize> = (0..n_nodes).collect();
+                let mut rng = Rng::new(*seed);
+                for i in 0..k {
+                    let j = i + rng.gen_range(n_nodes - i);
+                    nodes.swap(i, j);
+                }
+                nodes.truncate(k);
+                nodes
+            }
+        }
+    }
+}
+
+// Brandes' algorithm. The score of u sums, over ordered pairs (s, t), the fraction of
+// shortest s-t paths through u, so pairs of an undirected graph are counted in both directions.
+pub fn betweenness(graph: &CSR, sources: &Sources, n_thread: usize) -> Vec<f64>{
+    let (node_bc, _) = brandes(graph, sources, n_thread);
+    return node_bc;
+}
+
+// Like `betweenness`, per position in graph.edges, i.e. per direction of an undirected edge.
+pub fn edge_betweenness(graph: &CSR, sources: &Sources, n_thread: usize) -> Vec<f64>{
+    let (_, edge_bc) = brandes(graph, sources, n_thread);
+    return edge_bc;
+}
+
+fn brandes(graph: &CSR, sources: &Sources, n_thread: usize) -> (Vec<f64>, Vec<f64>){
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let sources = sources.select(n_nodes);
+
+    let mut node_bc = vec![0f64; n_nodes];
+    let mut edge_bc = vec![0f64; graph.edges.len()];
+
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for i in 0..n_thread {
+            let sources = &sources;
+            threads.push(scope.spawn(move |_| -> (Vec<f64>, Vec<f64>) {
+                let mut node_bc = vec![0f64; n_nodes];
+                let mut edge_bc = vec![0f64; graph.edges.len()];
+                let mut bfs = Bfs::new(n_nodes);
+                let mut sigma = vec![0f64; n_nodes];
+                let mut delta = vec![0f64; n_nodes];
+
+                for s in sources.iter().skip(i).step_by(n_thread) {
+                    // count shortest paths in BFS order
+                    bfs.run(graph, *s);
+                    for u in &bfs.order {
+                        sigma[*u] = 0.0;
+                        delta[*u] = 0.0;
+                    }
+                    sigma[*s] = 1.0;
+                    for u in &bfs.order {
+                        for v in graph.neighbors(*u) {
+                            if bfs.dist[*v] == bfs.dist[*u] + 1 {
+                                sigma[*v] += sigma[*u];
+                            }
+                        }
+                    }
+
+                    // accumulate dependencies from the farthest nodes
+                    for u in bfs.order.iter().rev() {
+                        for p in graph.nodes[*u]..graph.nodes[*u+1] {
+                            let v = graph.edges[p];
+                            if bfs.dist[v] == bfs.dist[*u] + 1 {
+                                let c = sigma[*u] / sigma[v] * (1.0 + delta[v]);
+                                delta[*u] += c;
+                                edge_bc[p] += c;
+                            }
+                        }
+                        if *u != *s {
+                            node_bc[*u] += delta[*u];
+                        }
+                    }
+                }
+
+                return (node_bc, edge_bc);
+            }));
+        }
+
+        for t in threads {
+            let (node_local, edge_local) = t.join().unwrap();
+            add_assign(&mut node_bc, &node_local);
+            add_assign(&mut edge_bc, &edge_local);
+        }
+    }).unwrap();
+
+    let scale = scale(n_nodes, sources.len());
+    node_bc.iter_mut().for_each(|x| *x *= scale);
+    edge_bc.iter_mut().for_each(|x| *x *= scale);
+
+    return (node_bc, edge_bc);
+}
+
+// Closeness from the distances of the other nodes to u, scaled by the fraction of nodes that
+// reach u (Wasserman & Faust) so that it stays comparable on disconnected graphs.
+pub fn closeness(graph: &CSR, sources: &Sources, n_thread: usize) -> Vec<f64>{
+    let n_nodes = graph.n_nodes();
+    let (dist_sums, reached, _) = distance_sums(graph, sources, n_thread);
+    let scale = scale(n_nodes, sources.select(n_nodes).len());
+
+    return (0..n_nodes).map(|u| {
+        if dist_sums[u] == 0.0 || n_nodes < 2 {
+            return 0.0;
+        }
+        return (reached[u] / dist_sums[u]) * (reached[u] * scale / (n_nodes - 1) as f64);
+    }).collect();
+}
+
+// Sum of the inverse distances of the other nodes to u.
+pub fn harmonic(graph: &CSR, sources: &Sources, n_thread: usize) -> Vec<f64>{
+    let n_nodes = graph.n_nodes();
+    let (_, _, inv_sums) = distance_sums(graph, sources, n_thread);
+    let scale = scale(n_nodes, sources.select(n_nodes).len());
+
+    return inv_sums.iter().map(|x| x * scale).collect();
+}
+
+// For each node u: the sum of distances from the sources reaching u, the number of such
+// sources and the sum of the inverse distances. Sources are not counted for themselves.
+fn distance_sums(graph: &CSR, sources: &Sources, n_thread: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>){
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let sources = sources.select(n_nodes);
+
+    let mut dist_sums = vec![0f64; n_nodes];
+    let mut reached = vec![0f64; n_nodes];
+    let mut inv_sums = vec![0f64; n_nodes];
+
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for i in 0..n_thread {
+            let sources = &sources;
+            threads.push(scope.spawn(move |_| -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+                let mut dist_sums = vec![0f64; n_nodes];
+                let mut reached = vec![0f64; n_nodes];
+                let mut inv_sums = vec![0f64; n_nodes];
+                let mut bfs = Bfs::new(n_nodes);
+
+                for s in sources.iter().skip(i).step_by(n_thread) {
+                    bfs.run(graph, *s);
+                    for u in bfs.order.iter().skip(1) {
+                        let d = bfs.dist[*u] as f64;
+                        dist_sums[*u] += d;
+                        reached[*u] += 1.0;
+                        inv_sums[*u] += 1.0 / d;
+                    }
+                }
+
+                return (dist_sums, reached, inv_sums);
+            }));
+        }
+
+        for t in threads {
+            let (d, r, h) = t.join().unwrap();
+            add_assign(&mut dist_sums, &d);
+            add_assign(&mut reached, &r);
+            add_assign(&mut inv_sums, &h);
+        }
+    }).unwrap();
+
+    return (dist_sums, reached, inv_sums);
+}
+
+fn scale(n_nodes: usize, n_sources: usize) -> f64{
+    if n_sources == 0 {
+        return 0.0;
+    }
+    return n_nodes as f64 / n_sources as f64;
+}
+
+fn add_assign(acc: &mut [f64], values: &[f64]){
+    for (a, v) in acc.iter_mut().zip(values) {
+        *a += v;
+    }
+}
+
+// BFS reusing its buffers across sources; only the visited entries are reset.
+struct Bfs{
+    dist: Vec<usize>,
+    order: Vec<usize>,
+    queue: VecDeque<usize>
+}
+
+impl Bfs{
+
+    fn new(n_nodes: usize) -> Self{
+        Self {
+            dist: vec![usize::MAX; n_nodes],
+            order: vec![],
+            queue: VecDeque::new()
+        }
+    }
+
+    fn run(&mut self, graph: &CSR, source: usize){
+        for u in self.order.drain(..) {
+            self.dist[u] = usize::MAX;
+        }
+
+        self.dist[source] = 0;
+        self.queue.push_back(source);
+        while let Some(u) = self.queue.pop_front() {
+            self.order.push(u);
+            for v in graph.neighbors(u) {
+                if self.dist[*v] == usize::MAX {
+                    self.dist[*v] = self.dist[u] + 1;
+                    self.queue.push_back(*v);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,314 @@
+// k-clique counting and listing (kClist, Danisch et al.) on oriented graphs,
+// where each undirected edge is stored once and the orientation is acyclic,
+// e.g. built from `kcore::oriented_edges` as for the triangle counting in `tricnt`.
+// Every k-clique is found exactly once, from its first node in the orientation.
+
+pub mod csr {
+
+    use crossbeam;
+
+    use crate::csr::CSR;
+
+    pub fn count(graph: &CSR, k: usize) -> usize{
+        assert!(k > 0, "k must be positive");
+
+        let mut buffers = vec![vec![]; k];
+        let mut cnt = 0usize;
+        for u in 0..graph.n_nodes() {
+            cnt += count_from(graph.neighbors(u), k - 1, graph, &mut buffers);
+        }
+        return cnt;
+    }
+
+    pub fn count_parallel(graph: &CSR, k: usize, n_thread: usize) -> usize{
+        assert!(k > 0, "k must be positive");
+        let n_thread = n_thread.max(1);
+
+        let mut cnt = 0usize;
+
+        crossbeam::scope(|scope| {
+            let mut threads = vec![];
+            for i in 0..n_thread{
+                threads.push(scope.spawn(move |_| -> usize {
+                    let mut buffers = vec![vec![]; k];
+                    let mut cnt = 0usize;
+                    for u in (i..graph.n_nodes()).step_by(n_thread) {
+                        cnt += count_from(graph.neighbors(u), k - 1, graph, &mut buffers);
+                    }
+                    return cnt;
+                }));
+            }
+
+            for t in threads {
+                cnt += t.join().unwrap();
+            }
+        }).unwrap();
+
+        return cnt;
+    }
+
+    // calls f with the nodes of each k-clique, in the order of the orientation.
+    pub fn list<F: FnMut(&[usize])>(graph: &CSR, k: usize, mut f: F){
+        assert!(k > 0, "k must be positive");
+
+        let mut buffers = vec![vec![]; k];
+        let mut clique = Vec::with_capacity(k);
+        for u in 0..graph.n_nodes() {
+            clique.push(u);
+            list_from(graph.neighbors(u), k - 1, graph, &mut buffers, &mut clique, &mut f);
+            clique.pop();
+        }
+    }
+
+    pub fn list_parallel<F: Fn(&[usize]) + Sync>(graph: &CSR, k: usize, n_thread: usize, f: F){
+        assert!(k > 0, "k must be positive");
+        let n_thread = n_thread.max(1);
+
+        let f = &f;
+        crossbeam::scope(|scope| {
+            for i in 0..n_thread{
+                scope.spawn(move |_| {
+                    let mut buffers = vec![vec![]; k];
+                    let mut clique = Vec::with_capacity(k);
+                    for u in (i..graph.n_nodes()).step_by(n_thread) {
+                        clique.push(u);
+                        list_from(graph.neighbors(u), k - 1, graph, &mut buffers, &mut clique, &mut |c: &[usize]| f(c));
+                        clique.pop();
+                    }
+                });
+            }
+        }).unwrap();
+    }
+
+    // number of l-cliques within the candidate set `cand`.
+    fn count_from(cand: &[usize], l: usize, graph: &CSR, buffers: &mut [Vec<usize>]) -> usize{
+        if l == 0 {
+            return 1;
+        }
+        if l == 1 {
+            return cand.len();
+        }
+
+        let (buf, rest) = buffers.split_first_mut().unwrap();
+        let mut cnt = 0usize;
+        for v in cand {
+            intersect_into(cand, graph.neighbors(*v), buf);
+            if buf.len() >= l - 1 {
+                cnt += count_from(buf, l - 1, graph, rest);
+            }
+        }
+        return cnt;
+    }
+
+    fn list_from<F: FnMut(&[usize])>(cand: &[usize], l: usize, graph: &CSR, buffers: &mut [Vec<usize>],
+                                     clique: &mut Vec<usize>, f: &mut F){
+        if l == 0 {
+            f(clique);
+            return;
+        }
+
+        let (buf, rest) = buffers.split_first_mut().unwrap();
+        for v in cand {
+            clique.push(*v);
+            if l == 1 {
+                f(clique);
+            }
+            else {
+                intersect_into(cand, graph.neighbors(*v), buf);
+                if buf.len() >= l - 1 {
+                    list_from(buf, l - 1, graph, rest, clique, f);
+                }
+            }
+            clique.pop();
+        }
+    }
+
+    pub fn intersect_into(a: &[usize], b: &[usize], out: &mut Vec<usize>){
+        out.clear();
+
+        let (mut i, mut j) = (0usize, 0usize);
+        while i < a.len() && j < b.len() {
+            if a[i] < b[j] {
+                i += 1;
+            }
+            else if a[i] > b[j] {
+                j += 1;
+            }
+            else {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+pub mod csbv {
+
+    use crossbeam;
+
+    use crate::csbv::CSBV;
+
+    const BLOCK_SIZE: usize = 64usize;
+
+    pub fn count(graph: &CSBV, k: usize) -> usize{
+        assert!(k > 0, "k must be positive");
+
+        let mut buffers = vec![vec![]; k];
+        let mut cand = vec![];
+        let mut cnt = 0usize;
+        for u in 0..graph.n_nodes() {
+            cand.clear();
+            cand.extend(graph.block_iter(u));
+            cnt += count_from(&cand, k - 1, graph, &mut buffers);
+        }
+        return cnt;
+    }
+
+    pub fn count_parallel(graph: &CSBV, k: usize, n_thread: usize) -> usize{
+        assert!(k > 0, "k must be positive");
+        let n_thread = n_thread.max(1);
+
+        let mut cnt = 0usize;
+
+        crossbeam::scope(|scope| {
+            let mut threads = vec![];
+            for i in 0..n_thread{
+                threads.push(scope.spawn(move |_| -> usize {
+                    let mut buffers = vec![vec![]; k];
+                    let mut cand = vec![];
+                    let mut cnt = 0usize;
+                    for u in (i..graph.n_nodes()).step_by(n_thread) {
+                        cand.clear();
+                        cand.extend(graph.block_iter(u));
+                        cnt += count_from(&cand, k - 1, graph, &mut buffers);
+                    }
+                    return cnt;
+                }));
+            }
+
+            for t in threads {
+                cnt += t.join().unwrap();
+            }
+        }).unwrap();
+
+        return cnt;
+    }
+
+    // calls f with the nodes of each k-clique, in the order of the orientation.
+    pub fn list<F: FnMut(&[usize])>(graph: &CSBV, k: usize, mut f: F){
+        assert!(k > 0, "k must be positive");
+
+        let mut buffers = vec![vec![]; k];
+        let mut cand = vec![];
+        let mut clique = Vec::with_capacity(k);
+        for u in 0..graph.n_nodes() {
+            cand.clear();
+            cand.extend(graph.block_iter(u));
+            clique.push(u);
+            list_from(&cand, k - 1, graph, &mut buffers, &mut clique, &mut f);
+            clique.pop();
+        }
+    }
+
+    pub fn list_parallel<F: Fn(&[usize]) + Sync>(graph: &CSBV, k: usize, n_thread: usize, f: F){
+        assert!(k > 0, "k must be positive");
+        let n_thread = n_thread.max(1);
+
+        let f = &f;
+        crossbeam::scope(|scope| {
+            for i in 0..n_thread{
+                scope.spawn(move |_| {
+                    let mut buffers = vec![vec![]; k];
+                    let mut cand = vec![];
+                    let mut clique = Vec::with_capacity(k);
+                    for u in (i..graph.n_nodes()).step_by(n_thread) {
+                        cand.clear();
+                        cand.extend(graph.block_iter(u));
+                        clique.push(u);
+                        list_from(&cand, k - 1, graph, &mut buffers, &mut clique, &mut |c: &[usize]| f(c));
+                        clique.pop();
+                    }
+                });
+            }
+        }).unwrap();
+    }
+
+    // number of l-cliques within the candidate set `cand`, given as (block id, bits) pairs.
+    fn count_from(cand: &[(usize, usize)], l: usize, graph: &CSBV, buffers: &mut [Vec<(usize, usize)>]) -> usize{
+        if l == 0 {
+            return 1;
+        }
+        if l == 1 {
+            return cand.iter().map(|(_, bits)| bits.count_ones() as usize).sum();
+        }
+
+        let (buf, rest) = buffers.split_first_mut().unwrap();
+        let mut cnt = 0usize;
+        for (bid, bits) in cand {
+            let mut bits = *bits;
+            while bits != 0 {
+                let offset = bits.trailing_zeros() as usize;
+                bits -= 1 << offset;
+
+                intersect_into(cand, graph, bid * BLOCK_SIZE + offset, buf);
+                if !buf.is_empty() {
+                    cnt += count_from(buf, l - 1, graph, rest);
+                }
+            }
+        }
+        return cnt;
+    }
+
+    fn list_from<F: FnMut(&[usize])>(cand: &[(usize, usize)], l: usize, graph: &CSBV, buffers: &mut [Vec<(usize, usize)>],
+                                     clique: &mut Vec<usize>, f: &mut F){
+        if l == 0 {
+            f(clique);
+            return;
+        }
+
+        let (buf, rest) = buffers.split_first_mut().unwrap();
+        for (bid, bits) in cand {
+            let mut bits = *bits;
+            while bits != 0 {
+                let offset = bits.trailing_zeros() as usize;
+                bits -= 1 << offset;
+                let v = bid * BLOCK_SIZE + offset;
+
+                clique.push(v);
+                if l == 1 {
+                    f(clique);
+                }
+                else {
+                    intersect_into(cand, graph, v, buf);
+                    if !buf.is_empty() {
+                        list_from(buf, l - 1, graph, rest, clique, f);
+                    }
+                }
+                clique.pop();
+            }
+        }
+    }
+
+    // intersects the candidate blocks with the neighbor blocks of v, keeping non-empty blocks only.
+    pub fn intersect_into(cand: &[(usize, usize)], graph: &CSBV, v: usize, out: &mut Vec<(usize, usize)>){
+        out.clear();
+
+        let mut i = 0usize;
+        for (vn, vn_bits) in graph.block_iter(v) {
+            while i < cand.len() && cand[i].0 < vn {
+                i += 1;
+            }
+            if i == cand.len() {
+                return;
+            }
+            if cand[i].0 == vn {
+                let bits = cand[i].1 & vn_bits;
+                if bits != 0 {
+                    out.push((vn, bits));
+                }
+                i += 1;
+            }
+        }
+    }
+}
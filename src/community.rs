@@ -0,0 +1,353 @@
+use crossbeam;
+
+use crate::rng::Rng;
+use crate::wcsr::WCSR;
+
+// Community detection over an undirected weighted CSR storing every edge in both directions
+// (see `WCSR::from_edges`, and `WCSR::from_csr` for unweighted graphs).
+// Community ids are renumbered to 0..k in the order of their first node.
+
+// Newman's modularity of a partition: sum over communities c of
+// in_c / 2m - (tot_c / 2m)^2, with in_c the weight inside c and tot_c the weighted degree of c.
+pub fn modularity(graph: &WCSR, communities: &[usize]) -> f64{
+    let n_comms = communities.iter().copied().max().map_or(0, |c| c + 1);
+    let mut inside = vec![0f64; n_comms];
+    let mut totals = vec![0f64; n_comms];
+    let mut two_m = 0f64;
+
+    for u in 0..graph.n_nodes() {
+        let cu = communities[u];
+        for (v, w) in graph.neighbors(u).iter().zip(graph.weights(u)) {
+            if communities[*v] == cu {
+                inside[cu] += w;
+            }
+            totals[cu] += w;
+            two_m += w;
+        }
+    }
+
+    if two_m == 0.0 {
+        return 0.0;
+    }
+
+    let mut q = 0f64;
+    for c in 0..n_comms {
+        q += inside[c] / two_m - (totals[c] / two_m) * (totals[c] / two_m);
+    }
+    return q;
+}
+
+pub fn renumber(communities: &[usize]) -> (Vec<usize>, usize){
+    let n_comms = communities.iter().copied().max().map_or(0, |c| c + 1);
+    let mut ids = vec![usize::MAX; n_comms];
+    let mut k = 0usize;
+
+    let renumbered = communities.iter().map(|c| {
+        if ids[*c] == usize::MAX {
+            ids[*c] = k;
+            k += 1;
+        }
+        ids[*c]
+    }).collect();
+
+    return (renumbered, k);
+}
+
+// Asynchronous label propagation (Raghavan et al.). Nodes are visited in a random order and
+// take the label of largest weight among their neighbors, ties being broken at random.
+pub fn label_propagation(graph: &WCSR, max_iter: usize, seed: u64) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let mut rng = Rng::new(seed);
+    let mut labels : Vec<usize> = (0..n_nodes).collect();
+    let mut order : Vec<usize> = (0..n_nodes).collect();
+    let mut label_weights = vec![0f64; n_nodes];
+    let mut touched = vec![];
+
+    for _ in 0..max_iter {
+        for i in (1..n_nodes).rev() {
+            order.swap(i, rng.gen_range(i + 1));
+        }
+
+        let mut changed = false;
+        for u in &order {
+            let u = *u;
+            for (v, w) in graph.neighbors(u).iter().zip(graph.weights(u)) {
+                if label_weights[labels[*v]] == 0.0 {
+                    touched.push(labels[*v]);
+                }
+                label_weights[labels[*v]] += w;
+            }
+
+            let best_weight = touched.iter().map(|l| label_weights[*l]).fold(0f64, f64::max);
+
+            // keep the current label when it is among the best ones
+            if !touched.is_empty() && label_weights[labels[u]] < best_weight {
+                // reservoir sampling among tied labels
+                let mut n_ties = 0usize;
+                for l in &touched {
+                    if label_weights[*l] == best_weight {
+                        n_ties += 1;
+                        if rng.gen_range(n_ties) == 0 {
+                            labels[u] = *l;
+                        }
+                    }
+                }
+                changed = true;
+            }
+
+            for l in touched.drain(..) {
+                label_weights[l] = 0.0;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    return renumber(&labels).0;
+}
+
+// Louvain method (Blondel et al.): nodes greedily move to the neighbor community with the largest
+// modularity gain, then communities are merged into nodes, until no move improves modularity.
+// Returns the communities and their modularity.
+pub fn louvain(graph: &WCSR) -> (Vec<usize>, f64){
+    return louvain_with(graph, local_moving);
+}
+
+// Louvain with parallel local moving (Lu, Halappanavar & Kalyanaraman): nodes of a same color of
+// a greedy coloring move concurrently, so that neighbors never move at the same time. The result
+// does not depend on the number of threads.
+pub fn louvain_parallel(graph: &WCSR, n_thread: usize) -> (Vec<usize>, f64){
+    return louvain_with(graph, |g| local_moving_parallel(g, n_thread));
+}
+
+fn louvain_with<F: Fn(&WCSR) -> Vec<usize>>(graph: &WCSR, local_moving: F) -> (Vec<usize>, f64){
+    let mut membership : Vec<usize> = (0..graph.n_nodes()).collect();
+    let mut level : Option<WCSR> = None;
+
+    loop {
+        let g = level.as_ref().unwrap_or(graph);
+        let (comms, k) = renumber(&local_moving(g));
+        if k == g.n_nodes() {
+            break;
+        }
+
+        for c in membership.iter_mut() {
+            *c = comms[*c];
+        }
+        level = Some(aggregate(g, &comms, k));
+    }
+
+    let q = modularity(graph, &membership);
+    return (membership, q);
+}
+
+fn weighted_degrees(graph: &WCSR) -> Vec<f64>{
+    return (0..graph.n_nodes()).map(|u| graph.weights(u).iter().sum()).collect();
+}
+
+// Weights from u to each neighbor community, self loops excluded.
+struct CommunityWeights{
+    weights: Vec<f64>,
+    touched: Vec<usize>
+}
+
+impl CommunityWeights{
+
+    fn new(n: usize) -> Self{
+        Self { weights: vec![0f64; n], touched: vec![] }
+    }
+
+    fn gather(&mut self, graph: &WCSR, u: usize, comms: &[usize]){
+        for c in self.touched.drain(..) {
+            self.weights[c] = 0.0;
+        }
+        // the current community is always a candidate. Communities linked by zero weights
+        // only may be listed twice, which is harmless.
+        self.touched.push(comms[u]);
+        for (v, w) in graph.neighbors(u).iter().zip(graph.weights(u)) {
+            if *v != u {
+                let c = comms[*v];
+                if self.weights[c] == 0.0 && c != comms[u] {
+                    self.touched.push(c);
+                }
+                self.weights[c] += w;
+            }
+        }
+    }
+}
+
+fn local_moving(graph: &WCSR) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let degrees = weighted_degrees(graph);
+    let two_m : f64 = degrees.iter().sum();
+
+    let mut comms : Vec<usize> = (0..n_nodes).collect();
+    let mut totals = degrees.clone();
+    let mut cw = CommunityWeights::new(n_nodes);
+
+    if two_m == 0.0 {
+        return comms;
+    }
+
+    loop {
+        let mut moved = false;
+        for u in 0..n_nodes {
+            let cu = comms[u];
+            cw.gather(graph, u, &comms);
+            totals[cu] -= degrees[u];
+
+            let best = best_community(&cw, cu, degrees[u], two_m, |c| totals[c]);
+            totals[best] += degrees[u];
+            if best != cu {
+                comms[u] = best;
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+
+    return comms;
+}
+
+// Sweeps over the color classes of a greedy coloring, in batches of at most BATCH_SIZE nodes:
+// the nodes of a batch, never adjacent, pick their best community concurrently, then their moves
+// are applied before the next batch. Bounded batches keep the community weights seen by each
+// node close to those of a sequential sweep. Since the nodes of a batch are not adjacent, the
+// modularity gain of a sweep is the sum of the gains of its moves, counted as they are applied.
+// Sweeps go on while they raise modularity by more than MIN_GAIN; a sweep lowering it, which
+// moves into a same community may cause, is undone.
+fn local_moving_parallel(graph: &WCSR, n_thread: usize) -> Vec<usize>{
+    const BATCH_SIZE: usize = 1024;
+    const MIN_GAIN: f64 = 1e-9;
+
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let degrees = weighted_degrees(graph);
+    let two_m : f64 = degrees.iter().sum();
+
+    let mut comms : Vec<usize> = (0..n_nodes).collect();
+    if two_m == 0.0 {
+        return comms;
+    }
+
+    let classes = color_classes(graph);
+    let mut totals = degrees.clone();
+    let mut cws : Vec<CommunityWeights> = (0..n_thread).map(|_| CommunityWeights::new(n_nodes)).collect();
+    // best community of each node of a batch, with the weight gained inside communities
+    let mut moves = vec![(0usize, 0f64); BATCH_SIZE];
+
+    loop {
+        let previous = comms.clone();
+        let mut gain = 0f64;
+        for batch in classes.iter().flat_map(|c| c.chunks(BATCH_SIZE)) {
+            let chunk_size = batch.len().div_ceil(n_thread);
+            crossbeam::scope(|scope| {
+                let chunks = batch.chunks(chunk_size).zip(moves.chunks_mut(chunk_size));
+                for ((nodes, moves), cw) in chunks.zip(cws.iter_mut()) {
+                    let (comms, totals, degrees) = (&comms, &totals, &degrees);
+                    scope.spawn(move |_| {
+                        for (u, m) in nodes.iter().zip(moves.iter_mut()) {
+                            let (u, cu) = (*u, comms[*u]);
+                            cw.gather(graph, u, comms);
+                            let best = best_community(cw, cu, degrees[u], two_m, |c| {
+                                if c == cu { totals[c] - degrees[u] } else { totals[c] }
+                            });
+                            *m = (best, cw.weights[best] - cw.weights[cu]);
+                        }
+                    });
+                }
+            }).unwrap();
+
+            for (u, (c, inner)) in batch.iter().zip(&moves) {
+                let (cu, d) = (comms[*u], degrees[*u]);
+                if *c != cu {
+                    let squares = totals[cu] * totals[cu] + totals[*c] * totals[*c];
+                    totals[cu] -= d;
+                    totals[*c] += d;
+                    let new_squares = totals[cu] * totals[cu] + totals[*c] * totals[*c];
+                    gain += 2.0 * inner / two_m - (new_squares - squares) / (two_m * two_m);
+                    comms[*u] = *c;
+                }
+            }
+        }
+
+        if gain < 0.0 {
+            return previous;
+        }
+        if gain <= MIN_GAIN {
+            break;
+        }
+    }
+
+    return comms;
+}
+
+// The community of largest modularity gain for u, staying in cu unless another one is strictly
+// better. total(c) is the weighted degree of c without u.
+fn best_community<F: Fn(usize) -> f64>(cw: &CommunityWeights, cu: usize, degree: f64, two_m: f64, total: F) -> usize{
+    // gain of joining c, up to a constant factor
+    let gain = |c: usize| cw.weights[c] - total(c) * degree / two_m;
+
+    let mut best = cu;
+    let mut best_gain = gain(cu);
+    for c in &cw.touched {
+        let g = gain(*c);
+        if g > best_gain + 1e-12 {
+            best = *c;
+            best_gain = g;
+        }
+    }
+    return best;
+}
+
+// Nodes grouped by the color of a greedy coloring in id order, self loops aside.
+fn color_classes(graph: &WCSR) -> Vec<Vec<usize>>{
+    let n_nodes = graph.n_nodes();
+    let mut colors = vec![usize::MAX; n_nodes];
+    // used[c] == u marks the colors of the neighbors of u
+    let mut used : Vec<usize> = vec![];
+    let mut classes : Vec<Vec<usize>> = vec![];
+
+    for u in 0..n_nodes {
+        for v in graph.neighbors(u) {
+            let c = colors[*v];
+            if *v != u && c != usize::MAX {
+                used[c] = u;
+            }
+        }
+        let c = (0..used.len()).find(|c| used[*c] != u).unwrap_or(used.len());
+        if c == used.len() {
+            used.push(usize::MAX);
+            classes.push(vec![]);
+        }
+        colors[u] = c;
+        classes[c].push(u);
+    }
+    return classes;
+}
+
+// Merges each community into a single node, keeping the inner weights as self loops.
+fn aggregate(graph: &WCSR, comms: &[usize], n_comms: usize) -> WCSR{
+    let mut edges : Vec<(usize, usize, f64)> = vec![];
+    for u in 0..graph.n_nodes() {
+        for (v, w) in graph.neighbors(u).iter().zip(graph.weights(u)) {
+            edges.push((comms[u], comms[*v], *w));
+        }
+    }
+    edges.sort_unstable_by_key(|(u, v, _)| (*u, *v));
+
+    let mut merged : Vec<(usize, usize, f64)> = vec![];
+    for (u, v, w) in edges {
+        match merged.last_mut() {
+            Some(last) if last.0 == u && last.1 == v => last.2 += w,
+            _ => merged.push((u, v, w))
+        }
+    }
+
+    return WCSR::from_sorted_edges(&merged, n_comms);
+}
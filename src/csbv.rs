@@ -2,6 +2,9 @@ use std::{fs::File, io::{Read, Write}};
 use std::io::{BufWriter, BufReader};
 use std::io::Result;
 
+use crate::csr::CSR;
+use crate::permute::{check_permutation, csr_from_pairs, order_to_rank};
+
 // Compressed Sparse Bit Vectors
 pub struct CSBV{
     pub bit_blocks: Vec<usize>,
@@ -72,7 +75,7 @@ impl CSBV{
         return Ok(csbv);
     }
 
-    pub fn block_iter(&self, u: usize) -> NeighborBlockIterator{
+    pub fn block_iter(&self, u: usize) -> NeighborBlockIterator<'_>{
         return NeighborBlockIterator{
             csbv: self,
             end: self.ptrs[u+1],
@@ -80,7 +83,7 @@ impl CSBV{
         }
     }
 
-    pub fn neighbor_iter(&self, u: usize) -> NeighborIterator{
+    pub fn neighbor_iter(&self, u: usize) -> NeighborIterator<'_>{
         let ptr = self.ptrs[u];
         
         return NeighborIterator{
@@ -91,6 +94,42 @@ impl CSBV{
         };
     }
 
+    // relabels node order[i] to i (see `permute`), using n_thread threads.
+    pub fn permute(&self, order: &[usize], n_thread: usize) -> CSBV{
+        return self.relabel(order, n_thread, false);
+    }
+
+    // like `permute`, orienting every edge from the lower to the higher new id.
+    pub fn permute_oriented(&self, order: &[usize], n_thread: usize) -> CSBV{
+        return self.relabel(order, n_thread, true);
+    }
+
+    fn relabel(&self, order: &[usize], n_thread: usize, oriented: bool) -> CSBV{
+        const BLOCK_SIZE: usize = 64usize;
+
+        let n_nodes = self.n_nodes();
+        check_permutation(order, n_nodes);
+        let rank = order_to_rank(order);
+
+        let csr : CSR = csr_from_pairs(n_nodes, n_nodes, oriented, n_thread, |u, push| {
+            for (bid, mut bits) in self.block_iter(u) {
+                while bits != 0 {
+                    let v = bid * BLOCK_SIZE + bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    let (ru, rv) = (rank[u], rank[v]);
+                    if !oriented {
+                        push(ru, rv);
+                    }
+                    else if ru != rv {
+                        push(ru.min(rv), ru.max(rv));
+                    }
+                }
+            }
+        });
+
+        return CSBV::from_sorted_edges(&csr.iter_edges().collect::<Vec<_>>(), n_nodes);
+    }
+
     // edges are sorted, and has no duplicate. Nodes in each edge is ordered.
     pub fn from_sorted_edges(edges: &[(usize, usize)], n_nodes: usize) -> CSBV{
         let mut u_prev = usize::MAX;
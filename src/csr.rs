@@ -1,6 +1,8 @@
 use std::fs::File;
 use std::io::{Result, BufWriter, BufReader, Write, Read};
 
+use crate::permute::{check_permutation, csr_from_pairs, order_to_rank};
+
 pub struct CSR{
     pub nodes: Vec<usize>,
     pub edges: Vec<usize>
@@ -110,6 +112,64 @@ impl CSR{
         return csr;
     }
 
+    // reverses every edge. Neighbor lists of the result are sorted.
+    pub fn transpose(&self) -> CSR{
+        let n_nodes = self.n_nodes();
+        let mut csr = CSR{
+            edges: vec![0; self.edges.len()],
+            nodes: vec![0; n_nodes + 1]
+        };
+
+        for v in &self.edges {
+            csr.nodes[*v + 1] += 1;
+        }
+        for i in 0..n_nodes {
+            csr.nodes[i+1] += csr.nodes[i];
+        }
+
+        let mut ptrs = csr.nodes.clone();
+        for (u, v) in self.iter_edges() {
+            csr.edges[ptrs[v]] = u;
+            ptrs[v] += 1;
+        }
+
+        return csr;
+    }
+
+    // sorts each neighbor list, as required by the intersection based algorithms.
+    pub fn sort_neighbors(&mut self){
+        for u in 0..self.n_nodes() {
+            self.edges[self.nodes[u]..self.nodes[u+1]].sort_unstable();
+        }
+    }
+
+    // relabels node order[i] to i (see `permute`), using n_thread threads. Neighbor lists of
+    // the result are sorted.
+    pub fn permute(&self, order: &[usize], n_thread: usize) -> CSR{
+        check_permutation(order, self.n_nodes());
+        let rank = order_to_rank(order);
+        return csr_from_pairs(self.n_nodes(), self.n_nodes(), false, n_thread, |u, push| {
+            for v in self.neighbors(u) {
+                push(rank[u], rank[*v]);
+            }
+        });
+    }
+
+    // like `permute`, keeping each edge once from the lower to the higher new id, without
+    // self loops, as expected by `tricnt::csr` and `clique::csr`.
+    pub fn permute_oriented(&self, order: &[usize], n_thread: usize) -> CSR{
+        check_permutation(order, self.n_nodes());
+        let rank = order_to_rank(order);
+        return csr_from_pairs(self.n_nodes(), self.n_nodes(), true, n_thread, |u, push| {
+            for v in self.neighbors(u) {
+                let (ru, rv) = (rank[u], rank[*v]);
+                if ru != rv {
+                    push(ru.min(rv), ru.max(rv));
+                }
+            }
+        });
+    }
+
     pub fn degree(&self, u: usize) -> usize {
         return self.nodes[u+1] - self.nodes[u];
     }
@@ -118,7 +178,7 @@ impl CSR{
         return &self.edges[self.nodes[u]..self.nodes[u+1]];
     }
 
-    pub fn iter_edges(&self) -> CSREdgeIterator{
+    pub fn iter_edges(&self) -> CSREdgeIterator<'_>{
         return CSREdgeIterator{
             csr: self,
             n_cur: 0,
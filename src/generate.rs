@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+
+use crate::csbv::CSBV;
+use crate::csr::CSR;
+use crate::rng::Rng;
+
+// Seeded generators of simple undirected graphs. Every generator returns edges (u, v) with
+// u < v, sorted and without duplicates, so they can be passed to `CSR::from_sorted_edges` and
+// `CSBV::from_sorted_edges` for triangle counting, or to `to_csr` for the undirected graph.
+
+// The undirected graph, with sorted neighbor lists.
+pub fn to_csr(edges: &[(usize, usize)], n_nodes: usize) -> CSR{
+    let mut csr = CSR::from_edges(edges, n_nodes);
+    csr.sort_neighbors();
+    return csr;
+}
+
+// The graph oriented from lower to higher ids, as expected by `tricnt::csbv`.
+pub fn to_csbv(edges: &[(usize, usize)], n_nodes: usize) -> CSBV{
+    return CSBV::from_sorted_edges(edges, n_nodes);
+}
+
+fn canonical(mut edges: Vec<(usize, usize)>) -> Vec<(usize, usize)>{
+    for e in edges.iter_mut() {
+        if e.0 > e.1 {
+            *e = (e.1, e.0);
+        }
+    }
+    edges.retain(|(u, v)| u != v);
+    edges.sort_unstable();
+    edges.dedup();
+    return edges;
+}
+
+// G(n, p): every pair is an edge with probability p. Gaps between consecutive edges are drawn
+// from the geometric distribution (Batagelj & Brandes), in O(n + m) time.
+pub fn erdos_renyi_gnp(n_nodes: usize, p: f64, seed: u64) -> Vec<(usize, usize)>{
+    let mut edges = vec![];
+    if p <= 0.0 || n_nodes < 2 {
+        return edges;
+    }
+    if p >= 1.0 {
+        return complete(n_nodes);
+    }
+
+    let mut rng = Rng::new(seed);
+    let log_q = (1.0 - p).ln();
+
+    // pairs (v, w) with w < v are scanned in order
+    let mut v = 1usize;
+    let mut w : i64 = -1;
+    while v < n_nodes {
+        let r = 1.0 - rng.gen_f64();
+        w += 1 + (r.ln() / log_q).floor() as i64;
+        while w >= v as i64 && v < n_nodes {
+            w -= v as i64;
+            v += 1;
+        }
+        if v < n_nodes {
+            edges.push((w as usize, v));
+        }
+    }
+
+    edges.sort_unstable();
+    return edges;
+}
+
+// G(n, m): m distinct pairs drawn uniformly.
+pub fn erdos_renyi_gnm(n_nodes: usize, n_edges: usize, seed: u64) -> Vec<(usize, usize)>{
+    let max_edges = n_nodes * n_nodes.saturating_sub(1) / 2;
+    assert!(n_edges <= max_edges, "too many edges for {} nodes", n_nodes);
+
+    let mut rng = Rng::new(seed);
+    let mut edges = HashSet::with_capacity(n_edges);
+    while edges.len() < n_edges {
+        let u = rng.gen_range(n_nodes);
+        let v = rng.gen_range(n_nodes);
+        if u != v {
+            edges.insert((u.min(v), u.max(v)));
+        }
+    }
+
+    let mut edges : Vec<(usize, usize)> = edges.into_iter().collect();
+    edges.sort_unstable();
+    return edges;
+}
+
+// Barabási–Albert preferential attachment: each new node links to m distinct earlier nodes,
+// chosen with probability proportional to their degree. Node m starts by linking to 0..m.
+pub fn barabasi_albert(n_nodes: usize, m: usize, seed: u64) -> Vec<(usize, usize)>{
+    assert!(m > 0, "m must be positive");
+
+    let mut rng = Rng::new(seed);
+    let mut edges = vec![];
+    // every node appears once per incident edge
+    let mut endpoints = vec![];
+    let mut targets = Vec::with_capacity(m);
+
+    for v in m..n_nodes {
+        targets.clear();
+        if v == m {
+            targets.extend(0..m);
+        }
+        else {
+            while targets.len() < m {
+                let u = endpoints[rng.gen_range(endpoints.len())];
+                if !targets.contains(&u) {
+                    targets.push(u);
+                }
+            }
+        }
+
+        for u in &targets {
+            edges.push((*u, v));
+            endpoints.push(*u);
+            endpoints.push(v);
+        }
+    }
+
+    edges.sort_unstable();
+    return edges;
+}
+
+// R-MAT (Chakrabarti et al.) on 2^scale nodes, i.e. a stochastic Kronecker graph with the 2x2
+// initiator [[a, b], [c, 1-a-b-c]]. Each of the n_edges samples picks one quadrant per level;
+// self loops and duplicates are removed, so fewer edges may be returned.
+// Graph500 uses (0.57, 0.19, 0.19).
+pub fn rmat(scale: usize, n_edges: usize, a: f64, b: f64, c: f64, seed: u64) -> Vec<(usize, usize)>{
+    assert!(a + b + c <= 1.0, "a + b + c must be at most 1");
+
+    let mut rng = Rng::new(seed);
+    let mut edges = Vec::with_capacity(n_edges);
+    for _ in 0..n_edges {
+        let (mut u, mut v) = (0usize, 0usize);
+        for _ in 0..scale {
+            let r = rng.gen_f64();
+            let (du, dv) = if r < a { (0, 0) } else if r < a + b { (0, 1) } else if r < a + b + c { (1, 0) } else { (1, 1) };
+            u = (u << 1) | du;
+            v = (v << 1) | dv;
+        }
+        edges.push((u, v));
+    }
+
+    return canonical(edges);
+}
+
+// Watts–Strogatz small world: a ring where each node links to its k/2 nearest nodes on each side,
+// after which every edge is rewired to a uniform random endpoint with probability beta.
+pub fn watts_strogatz(n_nodes: usize, k: usize, beta: f64, seed: u64) -> Vec<(usize, usize)>{
+    let half = k / 2;
+    assert!(n_nodes > k, "n_nodes must be larger than k");
+
+    let mut rng = Rng::new(seed);
+    let mut edge_set : HashSet<(usize, usize)> = HashSet::new();
+    let mut degrees = vec![2 * half; n_nodes];
+    let mut ring = vec![];
+    for u in 0..n_nodes {
+        for j in 1..=half {
+            let v = (u + j) % n_nodes;
+            ring.push((u, v));
+            edge_set.insert((u.min(v), u.max(v)));
+        }
+    }
+
+    for (u, v) in ring {
+        if rng.gen_f64() >= beta {
+            continue;
+        }
+        // a node adjacent to every other node keeps its edge
+        if degrees[u] >= n_nodes - 1 {
+            continue;
+        }
+
+        let mut w = rng.gen_range(n_nodes);
+        while w == u || edge_set.contains(&(u.min(w), u.max(w))) {
+            w = rng.gen_range(n_nodes);
+        }
+        edge_set.remove(&(u.min(v), u.max(v)));
+        edge_set.insert((u.min(w), u.max(w)));
+        degrees[v] -= 1;
+        degrees[w] += 1;
+    }
+
+    let mut edges : Vec<(usize, usize)> = edge_set.into_iter().collect();
+    edges.sort_unstable();
+    return edges;
+}
+
+// rows x cols lattice, node r * cols + c at row r and column c.
+pub fn grid(rows: usize, cols: usize) -> Vec<(usize, usize)>{
+    let mut edges = vec![];
+    for r in 0..rows {
+        for c in 0..cols {
+            let u = r * cols + c;
+            if c + 1 < cols {
+                edges.push((u, u + 1));
+            }
+            if r + 1 < rows {
+                edges.push((u, u + cols));
+            }
+        }
+    }
+    return edges;
+}
+
+pub fn complete(n_nodes: usize) -> Vec<(usize, usize)>{
+    let mut edges = vec![];
+    for u in 0..n_nodes {
+        for v in u+1..n_nodes {
+            edges.push((u, v));
+        }
+    }
+    return edges;
+}
+
+// node 0 linked to every other node.
+pub fn star(n_nodes: usize) -> Vec<(usize, usize)>{
+    return (1..n_nodes).map(|v| (0, v)).collect();
+}
+
+// n_cliques cliques of clique_size nodes, the last node of each clique linked to the first
+// node of the next one.
+pub fn clique_chain(n_cliques: usize, clique_size: usize) -> Vec<(usize, usize)>{
+    let mut edges = vec![];
+    for i in 0..n_cliques {
+        let first = i * clique_size;
+        for u in first..first + clique_size {
+            for v in u+1..first + clique_size {
+                edges.push((u, v));
+            }
+        }
+        if i + 1 < n_cliques && clique_size > 0 {
+            edges.push((first + clique_size - 1, first + clique_size));
+        }
+    }
+    return edges;
+}
+
+// Triangle counts of the deterministic generators. Grids and stars have none.
+
+pub fn complete_triangles(n_nodes: usize) -> usize{
+    return n_nodes * n_nodes.saturating_sub(1) * n_nodes.saturating_sub(2) / 6;
+}
+
+pub fn clique_chain_triangles(n_cliques: usize, clique_size: usize) -> usize{
+    return n_cliques * complete_triangles(clique_size);
+}
+
+// Watts–Strogatz without rewiring (beta = 0), valid when n_nodes > 3 * (k / 2).
+pub fn ring_lattice_triangles(n_nodes: usize, k: usize) -> usize{
+    let half = k / 2;
+    assert!(n_nodes > 3 * half, "n_nodes must be larger than 3 * (k / 2)");
+    return n_nodes * half * half.saturating_sub(1) / 2;
+}
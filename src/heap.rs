@@ -45,6 +45,128 @@ fn siftdown_indices(idx: usize, heap: &mut[usize], values: &[usize], size : usiz
 }
 
 
+// Min-heap over the indices 0..n with updatable keys, for algorithms that need decrease-key.
+pub struct IndexedHeap<T>{
+    heap: Vec<usize>,
+    positions: Vec<usize>,
+    keys: Vec<T>
+}
+
+impl<T: PartialOrd + Copy + Default> IndexedHeap<T>{
+
+    pub fn new(n: usize) -> Self{
+        Self {
+            heap: Vec::new(),
+            positions: vec![usize::MAX; n],
+            keys: vec![T::default(); n]
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        return self.heap.len();
+    }
+
+    pub fn is_empty(&self) -> bool{
+        return self.heap.is_empty();
+    }
+
+    pub fn contains(&self, idx: usize) -> bool{
+        return self.positions[idx] != usize::MAX;
+    }
+
+    pub fn key(&self, idx: usize) -> Option<T>{
+        if self.contains(idx) { Some(self.keys[idx]) } else { None }
+    }
+
+    pub fn peek(&self) -> Option<(usize, T)>{
+        return self.heap.first().map(|idx| (*idx, self.keys[*idx]));
+    }
+
+    // inserts idx, or moves it to its new key if it is already in the heap.
+    pub fn push(&mut self, idx: usize, key: T){
+        if self.contains(idx) {
+            let old = self.keys[idx];
+            self.keys[idx] = key;
+            if key < old {
+                self.siftup(self.positions[idx]);
+            }
+            else {
+                self.siftdown(self.positions[idx]);
+            }
+        }
+        else {
+            self.keys[idx] = key;
+            self.positions[idx] = self.heap.len();
+            self.heap.push(idx);
+            self.siftup(self.heap.len() - 1);
+        }
+    }
+
+    // lowers the key of idx, which is inserted if absent. Larger keys are ignored.
+    // Returns true if the key has changed.
+    pub fn decrease_key(&mut self, idx: usize, key: T) -> bool{
+        if self.contains(idx) && key >= self.keys[idx] {
+            return false;
+        }
+        self.push(idx, key);
+        return true;
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)>{
+        let top = *self.heap.first()?;
+
+        let last = self.heap.pop().unwrap();
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = 0;
+            self.siftdown(0);
+        }
+        self.positions[top] = usize::MAX;
+
+        return Some((top, self.keys[top]));
+    }
+
+    fn swap(&mut self, i: usize, j: usize){
+        self.heap.swap(i, j);
+        self.positions[self.heap[i]] = i;
+        self.positions[self.heap[j]] = j;
+    }
+
+    fn siftup(&mut self, mut idx: usize){
+        while idx > 0 {
+            let parent = (idx - 1) / 2;
+            if self.keys[self.heap[idx]] < self.keys[self.heap[parent]] {
+                self.swap(idx, parent);
+                idx = parent;
+            }
+            else { break; }
+        }
+    }
+
+    fn siftdown(&mut self, mut idx: usize){
+        let size = self.heap.len();
+        loop {
+            let left_idx = idx * 2 + 1;
+            let right_idx = left_idx + 1;
+
+            if left_idx >= size { break; }
+
+            let child = if right_idx >= size || self.keys[self.heap[left_idx]] <= self.keys[self.heap[right_idx]] {
+                left_idx
+            } else {
+                right_idx
+            };
+
+            if self.keys[self.heap[child]] < self.keys[self.heap[idx]] {
+                self.swap(idx, child);
+                idx = child;
+            }
+            else { break; }
+        }
+    }
+}
+
+
 
 
 
@@ -0,0 +1,179 @@
+use crossbeam;
+
+use crate::csr::CSR;
+use crate::permute::order_to_rank;
+
+// k-core decomposition over an undirected CSR (both directions of each edge are stored,
+// e.g. built by `CSR::from_edges`). Self loops are ignored.
+
+pub fn core_numbers(graph: &CSR) -> Vec<usize>{
+    let (cores, _) = core_numbers_with_order(graph);
+    return cores;
+}
+
+// Bucket-based O(m) peeling (Batagelj & Zaversnik).
+// Returns core numbers and the peeling order, i.e. order[i] is the i-th removed node.
+pub fn core_numbers_with_order(graph: &CSR) -> (Vec<usize>, Vec<usize>){
+    let n_nodes = graph.n_nodes();
+
+    let mut degrees = vec![0usize; n_nodes];
+    let mut max_degree = 0usize;
+    for u in 0..n_nodes {
+        degrees[u] = graph.neighbors(u).iter().filter(|v| **v != u).count();
+        if degrees[u] > max_degree {
+            max_degree = degrees[u];
+        }
+    }
+
+    // bins[d] is the start position of nodes with degree d in `order`
+    let mut bins = vec![0usize; max_degree + 1];
+    for d in &degrees {
+        bins[*d] += 1;
+    }
+
+    let mut start = 0usize;
+    for bin in bins.iter_mut() {
+        let cnt = *bin;
+        *bin = start;
+        start += cnt;
+    }
+
+    let mut order = vec![0usize; n_nodes];
+    let mut pos = vec![0usize; n_nodes];
+    for u in 0..n_nodes {
+        pos[u] = bins[degrees[u]];
+        order[pos[u]] = u;
+        bins[degrees[u]] += 1;
+    }
+
+    for d in (1..=max_degree).rev() {
+        bins[d] = bins[d-1];
+    }
+    bins[0] = 0;
+
+    for i in 0..n_nodes {
+        let v = order[i];
+        for u in graph.neighbors(v) {
+            let u = *u;
+            if degrees[u] > degrees[v] {
+                let du = degrees[u];
+                let pu = pos[u];
+                let pw = bins[du];
+                let w = order[pw];
+                if u != w {
+                    (order[pu], order[pw]) = (w, u);
+                    pos[u] = pw;
+                    pos[w] = pu;
+                }
+                bins[du] += 1;
+                degrees[u] -= 1;
+            }
+        }
+    }
+
+    return (degrees, order);
+}
+
+// Relabels nodes by `order` and orients each edge from the lower to the higher new id.
+// The result is sorted, so it can be passed to `CSR::from_sorted_edges` or `CSBV::from_sorted_edges`.
+pub fn oriented_edges(graph: &CSR, order: &[usize]) -> Vec<(usize, usize)>{
+    let rank = order_to_rank(order);
+
+    let mut edges : Vec<(usize, usize)> = graph.iter_edges()
+        .filter(|(u, v)| rank[*u] < rank[*v])
+        .map(|(u, v)| (rank[u], rank[v]))
+        .collect();
+
+    edges.sort_unstable();
+    edges.dedup();
+
+    return edges;
+}
+
+pub fn degeneracy(cores: &[usize]) -> usize{
+    return cores.iter().copied().max().unwrap_or(0);
+}
+
+// Returns the degeneracy k and the nodes of the maximum k-core.
+pub fn max_core(graph: &CSR) -> (usize, Vec<usize>){
+    let cores = core_numbers(graph);
+    let k = degeneracy(&cores);
+    let nodes = (0..graph.n_nodes()).filter(|u| cores[*u] == k).collect();
+    return (k, nodes);
+}
+
+// Iterative h-index refinement (Lü et al.). Every round recomputes the h-index of each
+// node from the previous round's values, which converges to the core numbers.
+pub fn core_numbers_parallel(graph: &CSR, n_thread: usize) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let chunk_size = n_nodes.div_ceil(n_thread).max(1);
+
+    let mut cores : Vec<usize> = (0..n_nodes)
+        .map(|u| graph.neighbors(u).iter().filter(|v| **v != u).count())
+        .collect();
+    let mut next_cores = cores.clone();
+
+    loop {
+        let mut changed = false;
+
+        crossbeam::scope(|scope| {
+            let mut threads = vec![];
+            for (i, chunk) in next_cores.chunks_mut(chunk_size).enumerate() {
+                let cores = &cores;
+                threads.push(scope.spawn(move |_| -> bool {
+                    let mut changed = false;
+                    let mut counts : Vec<usize> = vec![];
+                    for (j, next) in chunk.iter_mut().enumerate() {
+                        let u = i * chunk_size + j;
+                        let h = h_index(u, graph, cores, &mut counts);
+                        if h != cores[u] {
+                            changed = true;
+                        }
+                        *next = h;
+                    }
+                    return changed;
+                }));
+            }
+
+            for t in threads {
+                changed |= t.join().unwrap();
+            }
+        }).unwrap();
+
+        std::mem::swap(&mut cores, &mut next_cores);
+
+        if !changed {
+            break;
+        }
+    }
+
+    return cores;
+}
+
+// Largest h such that u has at least h neighbors whose value is >= h, bounded by values[u].
+fn h_index(u: usize, graph: &CSR, values: &[usize], counts: &mut Vec<usize>) -> usize{
+    let bound = values[u];
+    if bound == 0 {
+        return 0;
+    }
+
+    counts.clear();
+    counts.resize(bound + 1, 0);
+
+    for v in graph.neighbors(u) {
+        if *v != u {
+            counts[values[*v].min(bound)] += 1;
+        }
+    }
+
+    let mut cnt = 0usize;
+    for h in (1..=bound).rev() {
+        cnt += counts[h];
+        if cnt >= h {
+            return h;
+        }
+    }
+
+    return 0;
+}
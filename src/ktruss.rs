@@ -0,0 +1,251 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossbeam;
+
+use crate::csr::CSR;
+use crate::tricnt;
+
+// k-truss decomposition over an undirected CSR with sorted neighbor lists, no self loops and no
+// duplicate edges (see `CSR::from_edges` and `CSR::sort_neighbors`).
+// Per-edge values are aligned with graph.edges; both directions of an edge hold the same value.
+
+// Maps every position in graph.edges to the id of its undirected edge.
+// Edge ids are assigned in the order of (u, v) with u < v.
+fn edge_ids(graph: &CSR) -> (Vec<usize>, Vec<(usize, usize)>){
+    let mut eids = vec![usize::MAX; graph.edges.len()];
+    let mut edges = vec![];
+
+    for u in 0..graph.n_nodes() {
+        let neighbors = graph.neighbors(u);
+        assert!(neighbors.windows(2).all(|w| w[0] < w[1]) && !neighbors.contains(&u),
+                "the graph must have sorted neighbor lists, no self loops and no duplicate edges");
+        for p in graph.nodes[u]..graph.nodes[u+1] {
+            let v = graph.edges[p];
+            if u < v {
+                let q = graph.nodes[v] + graph.neighbors(v).binary_search(&u)
+                    .expect("the graph must be undirected with sorted neighbor lists");
+                eids[p] = edges.len();
+                eids[q] = edges.len();
+                edges.push((u, v));
+            }
+        }
+    }
+
+    return (eids, edges);
+}
+
+fn to_positions(eids: &[usize], values: &[usize]) -> Vec<usize>{
+    return eids.iter().map(|e| values[*e]).collect();
+}
+
+// Number of triangles containing each edge.
+pub fn support(graph: &CSR) -> Vec<usize>{
+    let mut sup = vec![0usize; graph.edges.len()];
+    for u in 0..graph.n_nodes() {
+        for p in graph.nodes[u]..graph.nodes[u+1] {
+            sup[p] = tricnt::csr::count_intersect(u, graph.edges[p], graph);
+        }
+    }
+    return sup;
+}
+
+pub fn support_parallel(graph: &CSR, n_thread: usize) -> Vec<usize>{
+    let n_thread = n_thread.max(1);
+    let mut sup = vec![0usize; graph.edges.len()];
+    let chunk_size = graph.n_nodes().div_ceil(n_thread).max(1);
+
+    crossbeam::scope(|scope| {
+        let mut rest : &mut [usize] = &mut sup;
+        for i in 0..n_thread {
+            let start = (i * chunk_size).min(graph.n_nodes());
+            let end = ((i + 1) * chunk_size).min(graph.n_nodes());
+            let (chunk, remain) = rest.split_at_mut(graph.nodes[end] - graph.nodes[start]);
+            rest = remain;
+
+            scope.spawn(move |_| {
+                for u in start..end {
+                    for p in graph.nodes[u]..graph.nodes[u+1] {
+                        chunk[p - graph.nodes[start]] = tricnt::csr::count_intersect(u, graph.edges[p], graph);
+                    }
+                }
+            });
+        }
+    }).unwrap();
+
+    return sup;
+}
+
+// Trussness of each edge by peeling edges in increasing order of support.
+pub fn trussness(graph: &CSR) -> Vec<usize>{
+    let (eids, edges) = edge_ids(graph);
+    let n_edges = edges.len();
+
+    let mut sup = vec![0usize; n_edges];
+    for (e, (u, v)) in edges.iter().enumerate() {
+        sup[e] = tricnt::csr::count_intersect(*u, *v, graph);
+    }
+
+    let max_sup = sup.iter().copied().max().unwrap_or(0);
+    let mut buckets : Vec<Vec<usize>> = vec![vec![]; max_sup + 1];
+    for e in 0..n_edges {
+        buckets[sup[e]].push(e);
+    }
+
+    let mut removed = vec![false; n_edges];
+    let mut truss = vec![0usize; n_edges];
+
+    for level in 0..=max_sup {
+        while let Some(e) = buckets[level].pop() {
+            // stale entries are left behind when the support of an edge drops
+            if removed[e] || sup[e] != level {
+                continue;
+            }
+
+            removed[e] = true;
+            truss[e] = level + 2;
+
+            let (u, v) = edges[e];
+            tricnt::csr::for_each_intersect(u, v, graph, |pu, pv| {
+                let (e1, e2) = (eids[pu], eids[pv]);
+                if removed[e1] || removed[e2] {
+                    return;
+                }
+                for f in [e1, e2] {
+                    if sup[f] > level {
+                        sup[f] -= 1;
+                        buckets[sup[f]].push(f);
+                    }
+                }
+            });
+        }
+    }
+
+    return to_positions(&eids, &truss);
+}
+
+// Level-synchronous peeling (PKT, Kabir & Madduri). All edges whose support equals the
+// current level are removed together, and the supports of the remaining edges are
+// decremented concurrently.
+pub fn trussness_parallel(graph: &CSR, n_thread: usize) -> Vec<usize>{
+    let n_thread = n_thread.max(1);
+    let (eids, edges) = edge_ids(graph);
+    let n_edges = edges.len();
+
+    let mut sup = vec![0usize; n_edges];
+    let chunk_size = n_edges.div_ceil(n_thread).max(1);
+    crossbeam::scope(|scope| {
+        for (i, chunk) in sup.chunks_mut(chunk_size).enumerate() {
+            let edges = &edges;
+            scope.spawn(move |_| {
+                for (j, s) in chunk.iter_mut().enumerate() {
+                    let (u, v) = edges[i * chunk_size + j];
+                    *s = tricnt::csr::count_intersect(u, v, graph);
+                }
+            });
+        }
+    }).unwrap();
+    let sup : Vec<AtomicUsize> = sup.into_iter().map(AtomicUsize::new).collect();
+
+    let mut removed = vec![false; n_edges];
+    let mut in_curr = vec![false; n_edges];
+    let mut truss = vec![0usize; n_edges];
+    let mut n_removed = 0usize;
+    let mut level = 0usize;
+
+    while n_removed < n_edges {
+        let mut curr : Vec<usize> = (0..n_edges)
+            .filter(|e| !removed[*e] && sup[*e].load(Ordering::Relaxed) == level)
+            .collect();
+
+        while !curr.is_empty() {
+            for e in &curr {
+                in_curr[*e] = true;
+            }
+
+            let mut next = vec![];
+            crossbeam::scope(|scope| {
+                let mut threads = vec![];
+                for i in 0..n_thread {
+                    let (curr, edges, eids, sup) = (&curr, &edges, &eids, &sup);
+                    let (removed, in_curr) = (&removed, &in_curr);
+                    threads.push(scope.spawn(move |_| -> Vec<usize> {
+                        let mut next = vec![];
+                        for e in curr.iter().skip(i).step_by(n_thread) {
+                            let (u, v) = edges[*e];
+                            tricnt::csr::for_each_intersect(u, v, graph, |pu, pv| {
+                                let (e1, e2) = (eids[pu], eids[pv]);
+                                if removed[e1] || removed[e2] {
+                                    return;
+                                }
+                                // a triangle with several edges in the frontier is handled by the smallest one
+                                if (in_curr[e1] && e1 < *e) || (in_curr[e2] && e2 < *e) {
+                                    return;
+                                }
+                                for f in [e1, e2] {
+                                    if !in_curr[f] && decrement_support(&sup[f], level) {
+                                        next.push(f);
+                                    }
+                                }
+                            });
+                        }
+                        return next;
+                    }));
+                }
+
+                for t in threads {
+                    next.extend(t.join().unwrap());
+                }
+            }).unwrap();
+
+            for e in &curr {
+                removed[*e] = true;
+                in_curr[*e] = false;
+                truss[*e] = level + 2;
+            }
+            n_removed += curr.len();
+
+            curr = next;
+        }
+
+        level += 1;
+    }
+
+    return to_positions(&eids, &truss);
+}
+
+// Decrements the support unless it already reached the current level.
+// Returns true if the edge has just dropped to the current level.
+fn decrement_support(sup: &AtomicUsize, level: usize) -> bool{
+    let mut cur = sup.load(Ordering::Relaxed);
+    while cur > level {
+        match sup.compare_exchange_weak(cur, cur - 1, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return cur - 1 == level,
+            Err(x) => cur = x
+        }
+    }
+    return false;
+}
+
+pub fn max_truss(truss: &[usize]) -> usize{
+    return truss.iter().copied().max().unwrap_or(0);
+}
+
+// The maximal k-truss: the subgraph of edges with trussness at least k. Node ids are kept.
+pub fn k_truss(graph: &CSR, truss: &[usize], k: usize) -> CSR{
+    let n_nodes = graph.n_nodes();
+    let mut csr = CSR{
+        edges: Vec::with_capacity(graph.edges.len()),
+        nodes: vec![0; n_nodes + 1]
+    };
+
+    for u in 0..n_nodes {
+        for p in graph.nodes[u]..graph.nodes[u+1] {
+            if truss[p] >= k {
+                csr.edges.push(graph.edges[p]);
+            }
+        }
+        csr.nodes[u+1] = csr.edges.len();
+    }
+
+    return csr;
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return, clippy::needless_range_loop)]
+
 pub mod union_find;
 pub mod tricnt;
 pub mod csr;
@@ -6,6 +8,27 @@ pub mod slashburn;
 pub mod csbv;
 // pub mod coo;
 pub mod vis;
+pub mod kcore;
+pub mod ktruss;
+pub mod clique;
+pub mod maxclique;
+pub mod motif;
+pub mod bipartite;
+pub mod butterfly;
+pub mod rank;
+pub mod wcsr;
+pub mod shortest_path;
+pub mod centrality;
+pub mod community;
+pub mod generate;
+pub mod sample;
+pub mod walk;
+pub mod view;
+pub mod stats;
+pub mod permute;
+pub mod reorder;
+pub mod spy;
+mod rng;
 
 #[cfg(test)]
 mod test{
@@ -211,4 +234,951 @@ mod test{
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_kcore(){
+        // a 4-clique (0..4) with a tail 3-4-5 and an isolated node 6
+        let edges = vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3), (3, 4), (4, 5)];
+        let n_nodes = 7;
+        let graph = csr::CSR::from_edges(&edges, n_nodes);
+
+        let true_cores = vec![3, 3, 3, 3, 1, 1, 0];
+
+        let (cores, order) = kcore::core_numbers_with_order(&graph);
+        assert_eq!(cores, true_cores);
+        assert_eq!(kcore::core_numbers_parallel(&graph, 3), true_cores);
+        assert_eq!(kcore::max_core(&graph), (3, vec![0, 1, 2, 3]));
+
+        // core numbers never decrease along the peeling order
+        for i in 1..n_nodes {
+            assert!(cores[order[i-1]] <= cores[order[i]]);
+        }
+
+        // each node has at most `degeneracy` out-neighbors after orientation
+        let oriented = csr::CSR::from_sorted_edges(&kcore::oriented_edges(&graph, &order), n_nodes);
+        assert_eq!(oriented.edges.len(), edges.len());
+        for u in 0..n_nodes {
+            assert!(oriented.degree(u) <= kcore::degeneracy(&cores));
+        }
+        assert_eq!(tricnt::csr::count(&oriented), 4);
+    }
+
+    #[test]
+    fn test_ktruss(){
+        // a 4-clique (0..4), a triangle 3-4-5 and a pendant edge 5-6
+        let edges = vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3), (3, 4), (3, 5), (4, 5), (5, 6)];
+        let n_nodes = 7;
+        let mut graph = csr::CSR::from_edges(&edges, n_nodes);
+        graph.sort_neighbors();
+
+        let true_truss = |u: usize, v: usize| if v <= 3 { 4 } else if u == 5 || v == 6 { 2 } else { 3 };
+
+        let sup = ktruss::support(&graph);
+        assert_eq!(sup, ktruss::support_parallel(&graph, 3));
+
+        let truss = ktruss::trussness(&graph);
+        assert_eq!(truss, ktruss::trussness_parallel(&graph, 3));
+        for (p, (u, v)) in graph.iter_edges().enumerate() {
+            assert_eq!(truss[p], true_truss(u.min(v), u.max(v)));
+        }
+        assert_eq!(ktruss::max_truss(&truss), 4);
+
+        let sub = ktruss::k_truss(&graph, &truss, 3);
+        assert_eq!(sub.edges.len(), 18);
+        assert_eq!(sub.neighbors(5), &[3, 4]);
+        assert_eq!(sub.degree(6), 0);
+
+        // duplicate edges and self loops are rejected
+        let mut dup = csr::CSR::from_edges(&[(0, 1), (0, 1), (1, 2), (0, 2)], 3);
+        dup.sort_neighbors();
+        assert!(std::panic::catch_unwind(|| ktruss::trussness(&dup)).is_err());
+        assert!(std::panic::catch_unwind(|| ktruss::trussness_parallel(&dup, 2)).is_err());
+        let mut self_loop = csr::CSR::from_edges(&[(0, 1), (1, 1), (1, 2), (0, 2)], 3);
+        self_loop.sort_neighbors();
+        assert!(std::panic::catch_unwind(|| ktruss::trussness(&self_loop)).is_err());
+    }
+
+    #[test]
+    fn test_clique(){
+        // a 6-clique on ids[0..6] and a triangle on ids[5..8], spread over several bit blocks
+        let ids = [3usize, 70, 100, 130, 140, 200, 250, 255];
+        let mut edges = vec![];
+        for u in 0..6 {
+            for v in u+1..6 {
+                edges.push((ids[u], ids[v]));
+            }
+        }
+        edges.extend([(ids[5], ids[6]), (ids[5], ids[7]), (ids[6], ids[7])]);
+        let n_nodes = 256;
+
+        // orienting edges by node id is acyclic
+        edges.sort();
+        let oriented = csr::CSR::from_sorted_edges(&edges, n_nodes);
+        let oriented_bv = csbv::CSBV::from_sorted_edges(&edges, n_nodes);
+
+        let true_counts = [0, 256, 18, 21, 15, 6, 1, 0];
+        for k in 1..true_counts.len() {
+            assert_eq!(clique::csr::count(&oriented, k), true_counts[k]);
+            assert_eq!(clique::csr::count_parallel(&oriented, k, 3), true_counts[k]);
+            assert_eq!(clique::csbv::count(&oriented_bv, k), true_counts[k]);
+            assert_eq!(clique::csbv::count_parallel(&oriented_bv, k, 3), true_counts[k]);
+        }
+        assert_eq!(clique::csr::count(&oriented, 3), tricnt::csr::count(&oriented));
+        // no thread count means one thread
+        assert_eq!(clique::csr::count_parallel(&oriented, 4, 0), true_counts[4]);
+        assert_eq!(clique::csbv::count_parallel(&oriented_bv, 4, 0), true_counts[4]);
+        let n_listed = std::sync::atomic::AtomicUsize::new(0);
+        clique::csr::list_parallel(&oriented, 4, 0, |_| { n_listed.fetch_add(1, std::sync::atomic::Ordering::Relaxed); });
+        clique::csbv::list_parallel(&oriented_bv, 4, 0, |_| { n_listed.fetch_add(1, std::sync::atomic::Ordering::Relaxed); });
+        assert_eq!(n_listed.into_inner(), 2 * true_counts[4]);
+
+        let mut cliques = vec![];
+        clique::csr::list(&oriented, 4, |c| cliques.push(c.to_vec()));
+        let mut cliques_bv = vec![];
+        clique::csbv::list(&oriented_bv, 4, |c| cliques_bv.push(c.to_vec()));
+        let cliques_par = std::sync::Mutex::new(vec![]);
+        clique::csbv::list_parallel(&oriented_bv, 4, 3, |c| cliques_par.lock().unwrap().push(c.to_vec()));
+        let mut cliques_par = cliques_par.into_inner().unwrap();
+
+        cliques.sort();
+        cliques_bv.sort();
+        cliques_par.sort();
+        assert_eq!(cliques.len(), 15);
+        assert_eq!(cliques, cliques_bv);
+        assert_eq!(cliques, cliques_par);
+
+        for c in &cliques {
+            for i in 0..c.len() {
+                for j in i+1..c.len() {
+                    assert!(edges.contains(&(c[i], c[j])));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_maxclique(){
+        let collect = |graph: &csr::CSR| {
+            let mut cliques = vec![];
+            let done = maxclique::maximal_cliques(graph, None, |c| {
+                let mut c = c.to_vec();
+                c.sort();
+                cliques.push(c);
+                return true;
+            });
+            assert!(done);
+            cliques.sort();
+            return cliques;
+        };
+
+        // a 4-clique (0..4), a triangle 3-4-5, a pendant edge 5-6 and an isolated node 7
+        let edges = vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3), (3, 4), (3, 5), (4, 5), (5, 6)];
+        let mut graph = csr::CSR::from_edges(&edges, 8);
+        graph.sort_neighbors();
+
+        assert_eq!(collect(&graph), vec![vec![0, 1, 2, 3], vec![3, 4, 5], vec![5, 6], vec![7]]);
+        assert_eq!(maxclique::maximum_clique(&graph, None), (vec![0, 1, 2, 3], true));
+
+        let mut n_visited = 0;
+        assert!(!maxclique::maximal_cliques(&graph, None, |_| { n_visited += 1; return n_visited < 2; }));
+        assert_eq!(n_visited, 2);
+
+        // the Petersen graph is triangle free, so its maximal cliques are its 15 edges
+        let edges = vec![(0usize, 1usize), (1, 2), (2, 3), (3, 4), (0, 4), (0, 5), (1, 6), (2, 7), (3, 8), (4, 9),
+                         (5, 7), (7, 9), (6, 9), (6, 8), (5, 8)];
+        let mut graph = csr::CSR::from_edges(&edges, 10);
+        graph.sort_neighbors();
+
+        let mut true_cliques : Vec<Vec<usize>> = edges.iter().map(|(u, v)| vec![*u.min(v), *u.max(v)]).collect();
+        true_cliques.sort();
+        assert_eq!(collect(&graph), true_cliques);
+        assert_eq!(maxclique::maximum_clique(&graph, None).0.len(), 2);
+
+        // a star too large for the bitset subproblems, with a triangle 0-1-2
+        let n_nodes = 5000;
+        let mut edges : Vec<(usize, usize)> = (1..n_nodes).map(|v| (0, v)).collect();
+        edges.push((1, 2));
+        let mut graph = csr::CSR::from_edges(&edges, n_nodes);
+        graph.sort_neighbors();
+
+        let cliques = collect(&graph);
+        assert_eq!(cliques.len(), n_nodes - 2);
+        assert_eq!(cliques[0], vec![0, 1, 2]);
+        assert_eq!(cliques[1], vec![0, 3]);
+        assert_eq!(maxclique::maximum_clique(&graph, None), (vec![0, 1, 2], true));
+    }
+
+    #[test]
+    fn test_motif(){
+        // a tailed triangle: triangle 0-1-2 with the tail 2-3
+        let edges = vec![(0usize, 1usize), (0, 2), (1, 2), (2, 3)];
+        let mut graph = csr::CSR::from_edges(&edges, 4);
+        graph.sort_neighbors();
+
+        let orbits = motif::orbit_counts(&graph);
+        assert_eq!(orbits[0], [2, 1, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(orbits[1], orbits[0]);
+        assert_eq!(orbits[2], [3, 0, 2, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(orbits[3], [1, 2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0]);
+
+        // a 4-clique (0..4), a 4-cycle 3-4-5-6 and a star centered at 6
+        let edges = vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3),
+                         (3, 4), (4, 5), (5, 6), (3, 6), (6, 7), (6, 8)];
+        let mut graph = csr::CSR::from_edges(&edges, 9);
+        graph.sort_neighbors();
+
+        let counts = motif::count(&graph);
+        assert_eq!(counts, motif::GraphletCounts{
+            edges: 12,
+            wedges: 15,
+            triangles: 4,
+            paths: 16,
+            stars: 7,
+            cycles: 1,
+            tailed_triangles: 6,
+            diamonds: 0,
+            cliques: 1
+        });
+    }
+
+    #[test]
+    fn test_butterfly(){
+        // K(3,3) on left 0..3 and right 0..3, a butterfly on left 3, 4 and right 3, 4,
+        // and a pendant edge (4, 5)
+        let mut edges = vec![];
+        for u in 0..3 {
+            for v in 0..3 {
+                edges.push((u, v));
+            }
+        }
+        edges.extend([(3, 3), (3, 4), (4, 3), (4, 4), (4, 5)]);
+        let graph = bipartite::Bipartite::from_edges(&edges, 5, 6);
+
+        assert_eq!(butterfly::count(&graph), 10);
+        assert_eq!(butterfly::count_parallel(&graph, 3), 10);
+        assert_eq!(butterfly::count_parallel(&graph, 0), 10);
+
+        let (left_cnt, right_cnt) = butterfly::count_per_vertex(&graph);
+        assert_eq!(left_cnt, vec![6, 6, 6, 1, 1]);
+        assert_eq!(right_cnt, vec![6, 6, 6, 1, 1, 0]);
+
+        let true_edge_cnt = |u: usize| if u < 3 { 4 } else { 1 };
+        let edge_cnt = butterfly::count_per_edge(&graph);
+        let bitruss = butterfly::bitruss(&graph);
+        for u in 0..graph.n_left() {
+            for p in graph.left.nodes[u]..graph.left.nodes[u+1] {
+                let v = graph.left.edges[p];
+                let expected = if v == 5 { 0 } else { true_edge_cnt(u) };
+                assert_eq!(edge_cnt[p], expected);
+                assert_eq!(bitruss[p], expected);
+            }
+        }
+
+        // every edge of K(3,3) is in 4 butterflies, so sampling is exact on it
+        let graph = bipartite::Bipartite::from_edges(&edges[..9], 3, 3);
+        assert_eq!(butterfly::count_approx(&graph, 5, 42), 9.0);
+        assert_eq!(butterfly::count_per_vertex_approx(&graph, 5, 42), (vec![6.0; 3], vec![6.0; 3]));
+        assert_eq!(butterfly::count_per_edge_approx(&graph, 5, 42), vec![4.0; 9]);
+    }
+
+    #[test]
+    fn test_rank(){
+        // a directed cycle is uniform
+        let graph = csr::CSR::from_sorted_edges(&[(0, 1), (1, 2), (2, 0)], 3);
+        for r in rank::pagerank(&graph, 0.85, 1e-12, 100) {
+            assert!((r - 1.0 / 3.0).abs() < 1e-9);
+        }
+
+        // 0 -> 1, 0 -> 2, 1 -> 2, 2 -> 0, 3 -> 2 and a dangling node 4
+        let edges = vec![(0usize, 1usize), (0, 2), (1, 2), (2, 0), (3, 2)];
+        let graph = csr::CSR::from_sorted_edges(&edges, 5);
+        assert_eq!(graph.transpose().neighbors(2), &[0, 1, 3]);
+
+        let ranks = rank::pagerank(&graph, 0.85, 1e-12, 1000);
+        assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(ranks[2] > ranks[0] && ranks[0] > ranks[1] && ranks[1] > ranks[3]);
+        assert!((ranks[3] - ranks[4]).abs() < 1e-12);
+
+        let ranks_par = rank::pagerank_parallel(&graph, 0.85, 1e-12, 1000, 2);
+        for u in 0..5 {
+            assert!((ranks[u] - ranks_par[u]).abs() < 1e-9);
+        }
+
+        let ppr = rank::personalized_pagerank(&graph, &[3], 0.85, 1e-12, 1000);
+        let ppr_par = rank::personalized_pagerank_parallel(&graph, &[3], 0.85, 1e-12, 1000, 3);
+        assert!((ppr.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!((ppr[3] - 0.15).abs() < 1e-9);
+        assert_eq!(ppr[4], 0.0);
+
+        let local = rank::local_ppr(&graph, &[3], 0.85, 1e-10);
+        for u in 0..5 {
+            assert!((ppr[u] - ppr_par[u]).abs() < 1e-9);
+            assert!((ppr[u] - local[u]).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_indexed_heap(){
+        let mut heap = heap::IndexedHeap::new(6);
+        for (i, key) in [5usize, 3, 7, 1, 9, 4].iter().enumerate() {
+            heap.push(i, *key);
+        }
+
+        assert!(heap.decrease_key(2, 0));
+        assert!(!heap.decrease_key(3, 2));
+        heap.push(4, 2);
+
+        let mut popped = vec![];
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![(2, 0), (3, 1), (4, 2), (1, 3), (5, 4), (0, 5)]);
+        assert!(!heap.contains(0));
+    }
+
+    #[test]
+    fn test_shortest_path(){
+        let edges = vec![(0usize, 1usize, 4.0), (0, 2, 1.0), (1, 3, 1.0), (2, 1, 2.0), (2, 3, 5.0), (3, 4, 3.0), (5, 0, 1.0)];
+        let graph = wcsr::WCSR::from_sorted_edges(&edges, 6);
+
+        let true_dist = vec![0.0, 3.0, 1.0, 4.0, 7.0, f64::INFINITY];
+
+        let (dist, pred) = shortest_path::dijkstra(&graph, 0);
+        assert_eq!(dist, true_dist);
+        assert_eq!(shortest_path::path(&pred, 0, 4), Some(vec![0, 2, 1, 3, 4]));
+        assert_eq!(shortest_path::path(&pred, 0, 5), None);
+
+        for delta in [0.5, 2.0, 10.0] {
+            for n_thread in 1..4 {
+                assert_eq!(shortest_path::delta_stepping(&graph, 0, delta, n_thread), (dist.clone(), pred.clone()));
+            }
+        }
+        assert_eq!(shortest_path::bellman_ford(&graph, 0), Some((dist.clone(), pred.clone())));
+        assert_eq!(shortest_path::all_pairs(&graph, 2)[0], (dist, pred));
+
+        // a negative edge, and a negative cycle 1 -> 3 -> 1
+        let mut edges = edges;
+        edges[4] = (2, 3, -2.0);
+        let graph = wcsr::WCSR::from_sorted_edges(&edges, 6);
+        let (dist, _) = shortest_path::bellman_ford(&graph, 0).unwrap();
+        assert_eq!(dist, vec![0.0, 3.0, 1.0, -1.0, 2.0, f64::INFINITY]);
+
+        edges.insert(3, (3, 1, -1.5));
+        edges.sort_by_key(|(u, v, _)| (*u, *v));
+        let graph = wcsr::WCSR::from_sorted_edges(&edges, 6);
+        assert_eq!(shortest_path::bellman_ford(&graph, 0), None);
+        assert_eq!(shortest_path::bellman_ford(&graph, 4).unwrap().0[4], 0.0);
+    }
+
+    #[test]
+    fn test_centrality(){
+        // a path 0-1-2-3-4
+        let edges = vec![(0usize, 1usize), (1, 2), (2, 3), (3, 4)];
+        let mut graph = csr::CSR::from_edges(&edges, 5);
+        graph.sort_neighbors();
+
+        let all = centrality::Sources::All;
+        assert_eq!(centrality::betweenness(&graph, &all, 2), vec![0.0, 6.0, 8.0, 6.0, 0.0]);
+
+        // 0->1, 1->0, 1->2, 2->1, ...
+        let edge_bc = centrality::edge_betweenness(&graph, &all, 3);
+        assert_eq!(edge_bc, vec![4.0, 4.0, 6.0, 6.0, 6.0, 6.0, 4.0, 4.0]);
+
+        let closeness = centrality::closeness(&graph, &all, 2);
+        assert!((closeness[0] - 0.4).abs() < 1e-12);
+        assert!((closeness[2] - 4.0 / 6.0).abs() < 1e-12);
+
+        let harmonic = centrality::harmonic(&graph, &all, 1);
+        assert!((harmonic[0] - (1.0 + 1.0 / 2.0 + 1.0 / 3.0 + 1.0 / 4.0)).abs() < 1e-12);
+        assert!((harmonic[2] - 3.0).abs() < 1e-12);
+
+        // sampling every node is exact, and a seed gives reproducible estimates
+        let pivots = centrality::Sources::Pivots{ n_pivots: 10, seed: 7 };
+        assert_eq!(centrality::betweenness(&graph, &pivots, 2), centrality::betweenness(&graph, &all, 1));
+
+        let pivots = centrality::Sources::Pivots{ n_pivots: 3, seed: 7 };
+        let estimate = centrality::betweenness(&graph, &pivots, 2);
+        assert_eq!(estimate, centrality::betweenness(&graph, &pivots, 3));
+        assert!(estimate[2] > 0.0);
+    }
+
+    #[test]
+    fn test_community(){
+        // two 4-cliques joined by the edge 3-4
+        let mut edges = vec![];
+        for c in [0usize, 4] {
+            for u in c..c+4 {
+                for v in u+1..c+4 {
+                    edges.push((u, v, 1.0));
+                }
+            }
+        }
+        edges.push((3, 4, 1.0));
+        let graph = wcsr::WCSR::from_edges(&edges, 8);
+
+        let true_comms = vec![0, 0, 0, 0, 1, 1, 1, 1];
+        let true_q = 2.0 * (12.0 / 26.0 - 0.25);
+        assert!((community::modularity(&graph, &true_comms) - true_q).abs() < 1e-12);
+        assert_eq!(community::modularity(&graph, &[0; 8]), 0.0);
+
+        let (comms, q) = community::louvain(&graph);
+        assert_eq!(comms, true_comms);
+        assert!((q - true_q).abs() < 1e-12);
+
+        for n_thread in 1..4 {
+            let (comms, q) = community::louvain_parallel(&graph, n_thread);
+            assert_eq!(comms, true_comms);
+            assert!((q - true_q).abs() < 1e-12);
+        }
+
+        // parallel local moving gets close to the sequential modularity on random graphs
+        let random_graphs = [generate::erdos_renyi_gnp(1000, 0.01, 3), generate::barabasi_albert(1000, 3, 3)];
+        for edges in random_graphs {
+            let graph = wcsr::WCSR::from_csr(&generate::to_csr(&edges, 1000));
+            let (_, q) = community::louvain(&graph);
+            let (comms, q_par) = community::louvain_parallel(&graph, 3);
+            assert!(q_par > q - 0.02, "parallel {} sequential {}", q_par, q);
+            assert!((community::modularity(&graph, &comms) - q_par).abs() < 1e-12);
+            assert_eq!(community::louvain_parallel(&graph, 1).0, comms);
+        }
+
+        let labels = community::label_propagation(&graph, 100, 1);
+        assert_eq!(labels, community::label_propagation(&graph, 100, 1));
+        assert_eq!(community::renumber(&labels).0, true_comms);
+        assert!(community::modularity(&graph, &labels) > 0.0);
+        assert_eq!(community::renumber(&[5, 2, 5, 7]), (vec![0, 1, 0, 2], 3));
+    }
+
+    #[test]
+    fn test_generate(){
+        let is_simple = |edges: &[(usize, usize)], n_nodes: usize| {
+            edges.windows(2).all(|w| w[0] < w[1]) && edges.iter().all(|(u, v)| u < v && *v < n_nodes)
+        };
+        let triangles = |edges: &[(usize, usize)], n_nodes: usize| {
+            tricnt::csr::count(&csr::CSR::from_sorted_edges(edges, n_nodes))
+        };
+
+        let edges = generate::complete(7);
+        assert_eq!(edges.len(), 21);
+        assert_eq!(triangles(&edges, 7), generate::complete_triangles(7));
+        assert_eq!(tricnt::csbv::count(&generate::to_csbv(&edges, 7)), 35);
+
+        let edges = generate::clique_chain(4, 5);
+        assert!(is_simple(&edges, 20));
+        assert_eq!(triangles(&edges, 20), generate::clique_chain_triangles(4, 5));
+
+        let edges = generate::watts_strogatz(30, 6, 0.0, 1);
+        assert!(is_simple(&edges, 30));
+        assert_eq!(triangles(&edges, 30), generate::ring_lattice_triangles(30, 6));
+
+        assert_eq!(triangles(&generate::grid(4, 5), 20), 0);
+        assert_eq!(generate::grid(4, 5).len(), 31);
+        assert_eq!(generate::to_csr(&generate::star(10), 10).degree(0), 9);
+
+        let edges = generate::erdos_renyi_gnm(100, 300, 3);
+        assert!(is_simple(&edges, 100));
+        assert_eq!(edges.len(), 300);
+        assert_eq!(edges, generate::erdos_renyi_gnm(100, 300, 3));
+
+        let edges = generate::erdos_renyi_gnp(200, 0.1, 3);
+        assert!(is_simple(&edges, 200));
+        assert!(edges.len() > 1700 && edges.len() < 2300);
+        assert_eq!(generate::erdos_renyi_gnp(5, 1.0, 3), generate::complete(5));
+
+        let edges = generate::barabasi_albert(100, 3, 5);
+        assert!(is_simple(&edges, 100));
+        assert_eq!(edges.len(), 3 * 97);
+
+        let edges = generate::rmat(8, 2000, 0.57, 0.19, 0.19, 9);
+        assert!(is_simple(&edges, 256));
+        assert_eq!(edges, generate::rmat(8, 2000, 0.57, 0.19, 0.19, 9));
+
+        let edges = generate::watts_strogatz(100, 4, 0.3, 2);
+        assert!(is_simple(&edges, 100));
+        assert_eq!(edges.len(), 200);
+    }
+
+    #[test]
+    fn test_sample(){
+        let n_nodes = 300;
+        let edges = generate::erdos_renyi_gnm(n_nodes, 1200, 4);
+        let graph = generate::to_csr(&edges, n_nodes);
+
+        // the subgraph induced by the returned nodes, relabeled
+        let check = |graph: &csr::CSR, (sub, nodes): &(csr::CSR, Vec<usize>), n_samples: usize| {
+            assert_eq!(sub.n_nodes(), n_samples);
+            assert_eq!(nodes.len(), n_samples);
+            assert!(nodes.windows(2).all(|w| w[0] < w[1]));
+            let mut expected = vec![];
+            for (i, u) in nodes.iter().enumerate() {
+                for v in graph.neighbors(*u) {
+                    if let Ok(j) = nodes.binary_search(v) {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            assert_eq!(sub.iter_edges().collect::<Vec<_>>(), expected);
+        };
+
+        check(&graph, &sample::node_sample(&graph, 50, 1), 50);
+        check(&graph, &sample::edge_sample(&graph, 50, 1), 50);
+        check(&graph, &sample::random_walk(&graph, 50, 0.15, 1), 50);
+        check(&graph, &sample::metropolis_hastings_walk(&graph, 50, 1), 50);
+        check(&graph, &sample::forest_fire(&graph, 50, 0.7, 1), 50);
+        check(&graph, &sample::snowball(&graph, 50, 3, 1), 50);
+        check(&graph, &sample::node_sample(&graph, 1000, 1), n_nodes);
+
+        assert_eq!(sample::forest_fire(&graph, 80, 0.7, 2).1, sample::forest_fire(&graph, 80, 0.7, 2).1);
+        assert_eq!(sample::random_walk(&graph, 80, 0.15, 2).1, sample::random_walk(&graph, 80, 0.15, 2).1);
+
+        // walks jump out of small components
+        let graph = generate::to_csr(&generate::clique_chain(1, 3), 10);
+        check(&graph, &sample::random_walk(&graph, 10, 0.15, 3), 10);
+        check(&graph, &sample::metropolis_hastings_walk(&graph, 8, 3), 8);
+        check(&graph, &sample::snowball(&graph, 6, 1, 3), 6);
+        check(&graph, &sample::edge_sample(&graph, 10, 3), 3);
+
+        // snowball sampling from a star center selects the neighbors first
+        let graph = generate::to_csr(&generate::star(20), 20);
+        let (sub, nodes) = sample::snowball(&graph, 20, usize::MAX, 0);
+        assert_eq!(nodes, (0..20).collect::<Vec<_>>());
+        assert_eq!(sub.edges.len(), 38);
+    }
+
+    #[test]
+    fn test_walk(){
+        use std::sync::Mutex;
+
+        let n_nodes = 100;
+        let graph = generate::to_csr(&generate::watts_strogatz(n_nodes, 4, 0.2, 1), n_nodes);
+        let collect = |walker: &walk::Walker, n_thread: usize| {
+            let walks = Mutex::new(vec![]);
+            walk::walks(walker, 10, 3, 7, n_thread, |id, w| walks.lock().unwrap().push((id, w.to_vec())));
+            let mut walks = walks.into_inner().unwrap();
+            walks.sort_unstable();
+            walks.into_iter().map(|(_, w)| w).collect::<Vec<_>>()
+        };
+
+        let walks = collect(&walk::Walker::Uniform(&graph), 1);
+        assert_eq!(walks.len(), 3 * n_nodes);
+        assert_eq!(walks, collect(&walk::Walker::Uniform(&graph), 4));
+        for (i, w) in walks.iter().enumerate() {
+            assert_eq!(w.len(), 10);
+            assert_eq!(w[0], i % n_nodes);
+            assert!(w.windows(2).all(|e| graph.neighbors(e[0]).contains(&e[1])));
+        }
+
+        // the file lists the walks in order, whatever the number of threads
+        let filepath = "test_walk.txt";
+        walk::write_walks(&walk::Walker::Uniform(&graph), 10, 3, 7, 3, filepath).unwrap();
+        let lines : Vec<Vec<usize>> = std::fs::read_to_string(filepath).unwrap().lines()
+            .map(|l| l.split(' ').map(|x| x.parse().unwrap()).collect()).collect();
+        std::fs::remove_file(filepath).unwrap();
+        assert_eq!(lines, walks);
+
+        // node2vec with a small p mostly goes back
+        let walks = collect(&walk::Walker::Node2Vec{ graph: &graph, p: 0.01, q: 1.0 }, 2);
+        let n_back = walks.iter().flat_map(|w| w.windows(3)).filter(|w| w[0] == w[2]).count();
+        assert!(n_back as f64 > 0.9 * (8 * walks.len()) as f64);
+        assert!(walks.iter().all(|w| w.windows(2).all(|e| graph.neighbors(e[0]).contains(&e[1]))));
+
+        // zero weights are never followed
+        let weighted = wcsr::WCSR::from_edges(&[(0, 1, 1.0), (0, 2, 0.0), (1, 2, 2.0)], 3);
+        let walks = collect(&walk::Walker::Weighted(&weighted), 2);
+        assert!(walks.iter().all(|w| w.windows(2).all(|e| e != [0, 2] && e != [2, 0])));
+        assert!(walks.iter().all(|w| w.len() == 10));
+
+        // walks stop at nodes without out-neighbors
+        let path = csr::CSR::from_sorted_edges(&[(0, 1), (1, 2)], 3);
+        let walks = collect(&walk::Walker::Uniform(&path), 1);
+        assert_eq!(walks[0], vec![0, 1, 2]);
+        assert_eq!(walks[2], vec![2]);
+    }
+
+    #[test]
+    fn test_view(){
+        // 3x3 grid, node r * 3 + c
+        let graph = generate::to_csr(&generate::grid(3, 3), 9);
+
+        let mut view = view::FilteredCSR::from_nodes(&graph, &[0, 1, 2, 4, 7]);
+        assert_eq!(view.n_nodes(), 5);
+        assert_eq!(view.neighbors(1).collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(view.degree(4), 2);
+        assert_eq!(view.iter_edges().count(), 8);
+        view.remove(4);
+        assert!(!view.contains(4));
+        assert_eq!(view.degree(1), 2);
+        assert_eq!(view.degree(7), 0);
+
+        let (sub, nodes) = view.to_csr();
+        assert_eq!(nodes, vec![0, 1, 2, 7]);
+        assert_eq!(sub.iter_edges().collect::<Vec<_>>(), vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+
+        let (sub, nodes) = view::induced_subgraph(&graph, &[4, 1, 3, 4]);
+        assert_eq!(nodes, vec![1, 3, 4]);
+        assert_eq!(sub.iter_edges().collect::<Vec<_>>(), vec![(0, 2), (1, 2), (2, 0), (2, 1)]);
+
+        assert_eq!(view::k_hop(&graph, 4, 0), vec![4]);
+        assert_eq!(view::k_hop(&graph, 4, 1), vec![4, 1, 3, 5, 7]);
+        let (sub, nodes) = view::ego_network(&graph, 0, 2);
+        assert_eq!(nodes, vec![0, 1, 2, 3, 4, 6]);
+        assert_eq!(sub.edges.len(), 2 * 6);
+        assert_eq!(view::ego_network(&graph, 0, 4).1, (0..9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_stats(){
+        // two triangles sharing node 2, a path 5-6-7, an isolated node 8,
+        // plus a self loop on 0 and a duplicate of (3, 4)
+        let edges = vec![(0, 1), (0, 2), (1, 2), (2, 3), (2, 4), (3, 4), (5, 6), (6, 7), (0, 0), (3, 4)];
+        let report = stats::report(&csr::CSR::from_edges(&edges, 9));
+
+        assert_eq!(report.n_nodes, 9);
+        assert_eq!(report.n_entries, 20);
+        assert_eq!(report.n_edges, 8);
+        assert_eq!(report.self_loops, 1);
+        assert_eq!(report.duplicate_edges, 1);
+        assert!(report.symmetric);
+        assert_eq!(report.isolated_nodes, 1);
+        assert_eq!((report.min_degree, report.max_degree), (0, 4));
+        assert_eq!(report.degree_histogram, vec![1, 2, 5, 0, 1]);
+        assert!((report.mean_degree - 16.0 / 9.0).abs() < 1e-12);
+        assert_eq!(report.component_sizes, vec![5, 3, 1]);
+        assert_eq!(report.n_components, 3);
+        assert_eq!(report.triangles, 2);
+        // 6 wedges at node 2, one at each other node of degree 2
+        assert!((report.transitivity - 6.0 / 11.0).abs() < 1e-12);
+        assert_eq!(report.degeneracy, 2);
+        assert_eq!(report.estimated_diameter, 2);
+
+        let json = report.to_json();
+        assert!(json.starts_with("{\n  \"n_nodes\": 9,\n"));
+        assert!(json.contains("\"component_sizes\": [5, 3, 1],"));
+        assert!(json.ends_with("\"estimated_diameter\": 2\n}"));
+
+        let graph = generate::to_csr(&generate::grid(5, 8), 40);
+        let report = stats::report(&graph);
+        assert_eq!(report.estimated_diameter, 11);
+        assert_eq!((report.triangles, report.degeneracy), (0, 2));
+        assert_eq!(report.n_edges, generate::grid(5, 8).len());
+
+        let report = stats::report(&csr::CSR::from_sorted_edges(&[(0, 1), (1, 2)], 3));
+        assert!(!report.symmetric);
+        assert_eq!(report.n_edges, 2);
+    }
+
+    #[test]
+    fn test_slashburn(){
+        // hub 5 linked to every node, a path 0-3-6 and an edge 1-7
+        let mut edges : Vec<(usize, usize)> = (0..8).filter(|u| *u != 5).map(|u| (u, 5)).collect();
+        edges.extend([(0, 3), (3, 6), (1, 7)]);
+        let graph = csr::CSR::from_edges(&edges, 8);
+        let config = |k: usize| slashburn::SlashBurnConfig{ k: slashburn::HubCount::Absolute(k), ..Default::default() };
+        let result = slashburn::slashburn(&graph, &config(1));
+        assert_eq!(result.order, vec![5, 3, 0, 6, 1, 7, 2, 4]);
+        assert_eq!(result.hubs, vec![5, 3, 0]);
+        assert_eq!(result.gcc_sizes, vec![3, 1, 0]);
+        // spokes of the first round: {1, 7}, then {2} and {4}
+        let groups : Vec<(usize, Vec<usize>)> = result.spokes[0].iter().map(|g| (g.component, g.nodes.clone())).collect();
+        assert_eq!(groups, vec![(1, vec![1, 7]), (2, vec![2]), (4, vec![4])]);
+        assert_eq!(slashburn::slashburn(&graph, &config(2)).order, vec![5, 3, 1, 7, 0, 2, 4, 6]);
+
+        // 0.005 * 8 rounds up to one hub per round
+        assert_eq!(slashburn::slashburn(&graph, &Default::default()).order, vec![5, 3, 0, 6, 1, 7, 2, 4]);
+        assert_eq!(slashburn::SlashBurnConfig{ k: slashburn::HubCount::Fraction(0.3), ..Default::default() }.hubs_per_round(8), 3);
+
+        // the giant component left after the first round fills the middle
+        let stopped = slashburn::SlashBurnConfig{ max_iterations: Some(1), ..config(1) };
+        assert_eq!(slashburn::slashburn(&graph, &stopped).order, vec![5, 0, 3, 6, 1, 7, 2, 4]);
+        let stopped = slashburn::SlashBurnConfig{ min_gcc_size: 4, ..config(1) };
+        assert_eq!(slashburn::slashburn(&graph, &stopped).order, vec![5, 0, 3, 6, 1, 7, 2, 4]);
+        // rules that already hold for the input remove no hub
+        for stopped in [slashburn::SlashBurnConfig{ max_iterations: Some(0), ..config(1) },
+                        slashburn::SlashBurnConfig{ min_gcc_size: 9, ..config(1) }] {
+            let result = slashburn::slashburn(&graph, &stopped);
+            assert_eq!(result.order, (0..8).collect::<Vec<_>>());
+            assert!(result.hubs.is_empty() && result.gcc_sizes.is_empty());
+        }
+
+        let edges = vec![(0, 1), (2, 3)];
+        let graph = csr::CSR::from_edges(&edges, 4);
+        let largest_first = slashburn::SlashBurnConfig{ tie_break: slashburn::TieBreak::LargestId, ..config(1) };
+        assert_eq!(slashburn::slashburn(&graph, &largest_first).order, vec![3, 1, 0, 2]);
+
+        let n_nodes = 500;
+        let graph = generate::to_csr(&generate::barabasi_albert(n_nodes, 2, 3), n_nodes);
+        let config = slashburn::SlashBurnConfig{ tie_break: slashburn::TieBreak::Random(1), ..config(5) };
+        let result = slashburn::slashburn(&graph, &config);
+        let order = result.order.clone();
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..n_nodes).collect::<Vec<_>>());
+        // the first hubs are the nodes of largest degree
+        let max_degree = (0..n_nodes).map(|u| graph.degree(u)).max().unwrap();
+        assert_eq!(graph.degree(order[0]), max_degree);
+
+        for n_thread in [1, 2, 4] {
+            assert_eq!(slashburn::slashburn_parallel(&graph, &config, n_thread).order, order);
+        }
+        for (u, r) in result.rank.iter().enumerate() {
+            assert_eq!(order[*r], u);
+        }
+        assert_eq!(result.hubs, order[..result.hubs.len()]);
+        assert_eq!(result.wing_width_ratio, result.hubs.len() as f64 / n_nodes as f64);
+
+        let mut rounds = vec![];
+        let traced = slashburn::slashburn_with_progress(&graph, &config, 2, |p| rounds.push((p.n_hubs, p.n_spokes, p.gcc_nodes)));
+        assert_eq!(traced.order, order);
+        assert_eq!(rounds.len(), result.gcc_sizes.len());
+        assert_eq!(rounds.iter().map(|r| r.2).collect::<Vec<_>>(), result.gcc_sizes);
+        assert_eq!(rounds.last().unwrap().0 + rounds.last().unwrap().1, n_nodes);
+    }
+
+    #[test]
+    fn test_permute(){
+        let n_nodes = 200;
+        let edges = generate::barabasi_albert(n_nodes, 3, 2);
+        let graph = generate::to_csr(&edges, n_nodes);
+        let order = slashburn::slashburn(&graph, &Default::default()).order;
+        let rank = permute::order_to_rank(&order);
+
+        let mut expected : Vec<(usize, usize)> = graph.iter_edges().map(|(u, v)| (rank[u], rank[v])).collect();
+        expected.sort_unstable();
+        let mut expected_oriented : Vec<(usize, usize)> = edges.iter().map(|(u, v)| (rank[*u].min(rank[*v]), rank[*u].max(rank[*v]))).collect();
+        expected_oriented.sort_unstable();
+
+        for n_thread in [1, 3, 8] {
+            let permuted = graph.permute(&order, n_thread);
+            assert_eq!(permuted.iter_edges().collect::<Vec<_>>(), expected);
+            for (i, u) in order.iter().enumerate() {
+                assert_eq!(permuted.degree(i), graph.degree(*u));
+            }
+
+            let oriented = graph.permute_oriented(&order, n_thread);
+            assert_eq!(oriented.iter_edges().collect::<Vec<_>>(), expected_oriented);
+            assert_eq!(tricnt::csr::count(&oriented), tricnt::csr::count(&csr::CSR::from_sorted_edges(&edges, n_nodes)));
+
+            assert_eq!(permute::permute_edges_oriented(&edges, &order, n_thread), expected_oriented);
+            let both : Vec<(usize, usize)> = graph.iter_edges().collect();
+            assert_eq!(permute::permute_edges(&both, &order, n_thread), expected);
+
+            let csbv = generate::to_csbv(&edges, n_nodes);
+            let relabeled = csbv.permute_oriented(&order, n_thread);
+            assert_eq!(tricnt::csbv::count(&relabeled), tricnt::csbv::count(&csbv));
+            let direct = csbv::CSBV::from_sorted_edges(&expected_oriented, n_nodes);
+            assert_eq!((relabeled.ptrs, relabeled.block_ids, relabeled.bit_blocks), (direct.ptrs, direct.block_ids, direct.bit_blocks));
+            let relabeled = csbv.permute(&order, n_thread);
+            let direct = csbv::CSBV::from_sorted_edges(&permute::permute_edges(&edges, &order, 1), n_nodes);
+            assert_eq!((relabeled.ptrs, relabeled.block_ids, relabeled.bit_blocks), (direct.ptrs, direct.block_ids, direct.bit_blocks));
+        }
+
+        // directions are kept without orientation
+        let path = csr::CSR::from_sorted_edges(&[(0, 1), (1, 2)], 3);
+        assert_eq!(path.permute(&[2, 1, 0], 2).iter_edges().collect::<Vec<_>>(), vec![(1, 0), (2, 1)]);
+        assert_eq!(path.permute_oriented(&[2, 1, 0], 2).iter_edges().collect::<Vec<_>>(), vec![(0, 1), (1, 2)]);
+
+        assert!(permute::is_permutation(&[2, 0, 1], 3));
+        assert!(!permute::is_permutation(&[2, 0, 0], 3));
+        assert!(!permute::is_permutation(&[0, 1, 3], 3));
+        assert!(!permute::is_permutation(&[0, 1], 3));
+        assert!(std::panic::catch_unwind(|| path.permute(&[0, 0, 1], 1)).is_err());
+    }
+
+    #[test]
+    fn test_reorder(){
+        let n_nodes = 300;
+        let graph = generate::to_csr(&generate::barabasi_albert(n_nodes, 2, 6), n_nodes);
+        let is_order = |order: &[usize], n_nodes: usize| permute::is_permutation(order, n_nodes);
+
+        let order = reorder::degree_sort(&graph);
+        assert!(is_order(&order, n_nodes));
+        assert!(order.windows(2).all(|w| graph.degree(w[0]) > graph.degree(w[1]) || graph.degree(w[0]) == graph.degree(w[1]) && w[0] < w[1]));
+
+        assert!(is_order(&reorder::random(n_nodes, 1), n_nodes));
+        assert_eq!(reorder::random(n_nodes, 1), reorder::random(n_nodes, 1));
+        assert_ne!(reorder::random(n_nodes, 1), reorder::random(n_nodes, 2));
+
+        for order in [reorder::bfs(&graph), reorder::rcm(&graph), reorder::gorder(&graph, 5), reorder::rabbit_order(&graph)] {
+            assert!(is_order(&order, n_nodes));
+        }
+
+        // a grid whose nodes are shuffled: RCM brings the bandwidth back to about one row
+        let (rows, cols) = (30, 8);
+        let n_nodes = rows * cols;
+        let grid = generate::to_csr(&generate::grid(rows, cols), n_nodes);
+        let shuffled = grid.permute(&reorder::random(n_nodes, 3), 1);
+        let bandwidth = |g: &csr::CSR| g.iter_edges().map(|(u, v)| u.abs_diff(v)).max().unwrap();
+        assert!(bandwidth(&shuffled) > 100);
+        assert!(bandwidth(&shuffled.permute(&reorder::rcm(&shuffled), 1)) <= cols + 1);
+        assert!(bandwidth(&shuffled.permute(&reorder::bfs(&shuffled), 1)) <= 2 * cols);
+
+        let rcm = reorder::evaluate(&shuffled, &reorder::rcm(&shuffled), 16);
+        let identity = reorder::evaluate(&shuffled, &(0..n_nodes).collect::<Vec<_>>(), 16);
+        assert_eq!(rcm.bandwidth, bandwidth(&shuffled.permute(&reorder::rcm(&shuffled), 1)));
+        assert_eq!(identity.bandwidth, bandwidth(&shuffled));
+        assert!(rcm.n_blocks < identity.n_blocks);
+        assert!(rcm.bits_per_block > identity.bits_per_block);
+        assert!(rcm.log_gap < identity.log_gap);
+        assert!(rcm.profile < identity.profile);
+        assert!(rcm.mean_gap < identity.mean_gap);
+        assert!(rcm.n_cells < identity.n_cells);
+
+        // a path 0-1-2-3 relabeled to 0-2-1-3
+        let path = generate::to_csr(&[(0, 1), (1, 2), (2, 3)], 4);
+        let metrics = reorder::evaluate(&path, &[0, 2, 1, 3], 2);
+        assert_eq!((metrics.n_blocks, metrics.bits_per_block), (2, 1.5));
+        assert_eq!((metrics.bandwidth, metrics.profile), (2, 4));
+        assert_eq!(metrics.mean_gap, 10.0 / 6.0);
+        // gaps 2 | 1, 1 | 2, 1 | 2
+        assert!((metrics.log_gap - (3.0 * 3f64.log2() + 3.0) / 6.0).abs() < 1e-12);
+        assert_eq!(metrics.n_cells, 2);
+
+        // BFS on a path from its first node, and RCM from an end
+        let path = generate::to_csr(&[(0, 2), (1, 2), (1, 3)], 4);
+        assert_eq!(reorder::bfs(&path), vec![0, 2, 1, 3]);
+        assert_eq!(reorder::rcm(&path), vec![0, 2, 1, 3]);
+
+        // cliques of a chain get consecutive positions, except that Rabbit order may put the
+        // node linking two cliques with the other one
+        let n_cliques = 6;
+        let size = 5;
+        let n_nodes = n_cliques * size;
+        let chain = generate::to_csr(&generate::clique_chain(n_cliques, size), n_nodes);
+        let shuffle = reorder::random(n_nodes, 4);
+        let shuffled = chain.permute(&shuffle, 1);
+        let n_runs = |order: &[usize]| 1 + order.windows(2).filter(|w| shuffle[w[0]] / size != shuffle[w[1]] / size).count();
+        assert_eq!(n_runs(&reorder::gorder(&shuffled, size)), n_cliques);
+        assert!(n_runs(&reorder::rabbit_order(&shuffled)) < 2 * n_cliques);
+    }
+
+    #[test]
+    fn test_spy(){
+        // two triangles {0, 1, 2} and {3, 4, 5} on 2 x 2 cells of 3 nodes
+        let edges = [(0, 1), (0, 2), (1, 2), (3, 4), (3, 5), (4, 5), (2, 3)];
+        let graph = generate::to_csr(&edges, 6);
+        let cells = vis::scaling(&graph.iter_edges().collect::<Vec<_>>(), 6, 2);
+        let config = spy::SpyConfig{ n_pixels: 2, cell_size: 2, scale: spy::Scale::Linear, grid: vec![] };
+        let image = spy::render(&cells, 6, &config);
+        assert_eq!((image.width, image.height), (4, 4));
+        // diagonal cells hold 6 entries, off-diagonal ones 1
+        assert_eq!(image.get(0, 0), [0; 3]);
+        assert_eq!(image.get(3, 3), [0; 3]);
+        assert_eq!(image.get(2, 1), [224; 3]);
+        assert_eq!(image.get(1, 2), [224; 3]);
+
+        let log = spy::render(&cells, 6, &spy::SpyConfig{ n_pixels: 2, cell_size: 1, scale: spy::Scale::Log, grid: vec![3] });
+        assert_eq!(log.get(0, 0), [0; 3]);
+        // the grid line runs across the second row and column
+        assert_eq!(log.get(1, 0), [255, 0, 0]);
+        assert_eq!(log.get(0, 1), [255, 0, 0]);
+
+        let ppm = image.to_ppm();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 48);
+
+        // stored blocks hold the filtered rows as they are
+        let png = image.to_png();
+        assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes(png[29..33].try_into().unwrap()), spy::crc32(&png[12..29]));
+        assert_eq!(&png[37..41], b"IDAT");
+        let raw = &png[41 + 2 + 5..41 + 2 + 5 + 52];
+        assert_eq!(&raw[..13], &[0, 0, 0, 0, 0, 0, 0, 224, 224, 224, 224, 224, 224]);
+        assert_eq!(u32::from_be_bytes(png[100..104].try_into().unwrap()), spy::adler32(raw));
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+        assert_eq!(spy::crc32(b"IEND"), 0xae426082);
+        assert_eq!(spy::adler32(b"Wikipedia"), 0x11e60398);
+
+        let svg = spy::to_svg(&cells, 6, &config);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 1 + cells.len());
+
+        let filepath = "test_spy.png";
+        image.write_png(filepath).unwrap();
+        assert_eq!(std::fs::read(filepath).unwrap(), png);
+        std::fs::remove_file(filepath).unwrap();
+
+        // hubs 0 and 1 at the front, the leaves other than 1 at the back
+        let star = generate::to_csr(&generate::star(7), 7);
+        let result = slashburn::slashburn(&star, &slashburn::SlashBurnConfig{ k: slashburn::HubCount::Absolute(1), ..Default::default() });
+        assert_eq!(result.boundaries(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_vis(){
+        let edges : Vec<(usize, usize)> = generate::rmat(8, 2000, 0.57, 0.19, 0.19, 3);
+        let n_nodes = 256;
+
+        // the square window counts the same cells as scaling, numbered by cell
+        for n_pixels in [7, 16, 100, 300] {
+            let window = vis::Window::square(n_nodes, n_pixels);
+            let mut cells : Vec<(usize, usize, usize)> = vis::scaling(&edges, n_nodes, n_pixels).iter()
+                .map(|(u, v, c)| (window.row_cell(*u).unwrap(), window.col_cell(*v).unwrap(), *c)).collect();
+            cells.sort_unstable();
+            assert_eq!(vis::scaling_window(&edges, &window), cells);
+        }
+
+        // rectangular windows of a zoomed block only count its edges
+        let window = vis::Window::new(10..40, 100..250, 3, 15);
+        let cells = vis::scaling_window(&edges, &window);
+        let inside = edges.iter().filter(|(u, v)| (10..40).contains(u) && (100..250).contains(v)).count();
+        assert_eq!(cells.iter().map(|c| c.2).sum::<usize>(), inside);
+        assert!(cells.iter().all(|(y, x, _)| *y < 3 && *x < 15));
+        assert_eq!(window.cell(19, 109), Some((0, 0)));
+        assert_eq!(window.cell(20, 110), Some((1, 1)));
+        assert_eq!(window.cell(39, 249), Some((2, 14)));
+        assert_eq!(window.cell(40, 100), None);
+
+        // a bipartite graph of 4 left and 6 right nodes
+        let bipartite = bipartite::Bipartite::from_edges(&[(0, 0), (0, 5), (1, 1), (3, 5)], 4, 6);
+        let window = vis::Window::new(0..bipartite.n_left(), 0..bipartite.n_right(), 2, 3);
+        let edges : Vec<(usize, usize)> = bipartite.left.iter_edges().collect();
+        assert_eq!(vis::scaling_window(&edges, &window), vec![(0, 0, 2), (0, 2, 1), (1, 2, 1)]);
+
+        let config = spy::SpyConfig{ cell_size: 2, scale: spy::Scale::Linear, grid: vec![2], ..Default::default() };
+        let image = spy::render_window(&vis::scaling_window(&edges, &window), &window, &config);
+        assert_eq!((image.width, image.height), (6, 4));
+        assert_eq!(image.get(1, 1), [0; 3]);
+        assert_eq!(image.get(5, 0), [224; 3]);
+        // the lines of row 2 and column 2 run along the second cell row and column
+        assert_eq!(image.get(4, 2), [255, 0, 0]);
+        assert_eq!(image.get(2, 3), [255, 0, 0]);
+        assert_eq!(image.get(3, 3), [255, 255, 255]);
+        let svg = spy::window_to_svg(&vis::scaling_window(&edges, &window), &window, &config);
+        assert!(svg.contains("width=\"6\" height=\"4\""));
+        assert_eq!(svg.matches("<line").count(), 2);
+
+        // dense counts agree with the cells of every source, whatever the number of threads
+        let n_nodes = 3000;
+        let graph = generate::to_csr(&generate::barabasi_albert(n_nodes, 4, 5), n_nodes);
+        let edges : Vec<(usize, usize)> = graph.iter_edges().collect();
+        let oriented : Vec<(usize, usize)> = edges.iter().copied().filter(|(u, v)| u < v).collect();
+        let csbv = csbv::CSBV::from_sorted_edges(&oriented, n_nodes);
+        let filepath = "test_vis_edges.txt";
+        let mut text = String::from("# u v weight\n\n");
+        for (u, v) in &edges {
+            text += &format!("{}\t{} 1.0\n", u, v);
+        }
+        std::fs::write(filepath, text.trim_end()).unwrap();
+
+        for window in [vis::Window::square(n_nodes, 64), vis::Window::new(0..n_nodes, 0..n_nodes, 37, 500),
+                       vis::Window::new(100..2100, 5..70, 9, 100), vis::Window::new(2900..3500, 0..4000, 200, 7)] {
+            let expected = vis::scaling_window(&edges, &window);
+            assert_eq!(vis::dense_cells(&vis::scaling_stream(graph.iter_edges(), &window), &window), expected);
+            for n_thread in [1, 3, 8] {
+                assert_eq!(vis::dense_cells(&vis::scaling_csr(&graph, &window, n_thread), &window), expected);
+                assert_eq!(vis::dense_cells(&vis::scaling_csbv(&csbv, &window, n_thread), &window), vis::scaling_window(&oriented, &window));
+                assert_eq!(vis::dense_cells(&vis::scaling_edge_list(filepath, &window, n_thread).unwrap(), &window), expected);
+            }
+        }
+        std::fs::write(filepath, "0 1\n2 x\n").unwrap();
+        assert!(vis::scaling_edge_list(filepath, &vis::Window::square(3, 3), 2).is_err());
+        std::fs::remove_file(filepath).unwrap();
+
+        // cells stay exact for ids close to usize::MAX
+        let n_nodes = 3usize << 62;
+        let u = n_nodes - 1;
+        assert_eq!(vis::Window::square(n_nodes, 1000).row_cell(u), Some(999));
+        assert_eq!(vis::scaling(&[(u, 0)], n_nodes, 1000), vec![(13821222997226881549, 0, 1)]);
+    }
+}
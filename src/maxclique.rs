@@ -0,0 +1,358 @@
+use std::time::{Duration, Instant};
+
+use crate::clique::csr::intersect_into;
+use crate::csr::CSR;
+use crate::kcore;
+use crate::permute;
+
+// Maximal clique enumeration and maximum clique search over an undirected CSR with
+// sorted neighbor lists and no self loops (see `CSR::from_edges` and `CSR::sort_neighbors`).
+// Both process nodes in degeneracy order, so each subproblem only holds the neighbors of one node.
+// Subproblems are solved on local adjacency bitsets of 64-bit blocks, as in CSBV.
+
+const BLOCK_SIZE: usize = 64usize;
+
+// subproblems with more nodes than this are solved on sorted neighbor lists instead of bitsets.
+const DENSE_LIMIT: usize = 4096usize;
+
+// Bron–Kerbosch with Tomita pivoting and degeneracy ordering (Eppstein, Löffler & Strash).
+// Calls f with the nodes of each maximal clique; f returns false to stop the enumeration.
+// Returns true if all maximal cliques have been visited.
+pub fn maximal_cliques<F: FnMut(&[usize]) -> bool>(graph: &CSR, time_limit: Option<Duration>, mut f: F) -> bool{
+    let (_, order) = kcore::core_numbers_with_order(graph);
+    let rank = permute::order_to_rank(&order);
+    let mut search = Search::new(time_limit);
+
+    for v in order {
+        let p : Vec<usize> = graph.neighbors(v).iter().copied().filter(|w| rank[*w] > rank[v]).collect();
+        let x : Vec<usize> = graph.neighbors(v).iter().copied().filter(|w| rank[*w] < rank[v]).collect();
+        let mut r = vec![v];
+
+        if p.len() + x.len() <= DENSE_LIMIT {
+            let locals = merge(&p, &x);
+            let sub = Subgraph::new(graph, &locals);
+            let pbits = sub.to_bits(&p);
+            let xbits = sub.to_bits(&x);
+            bk_bits(&mut r, pbits, xbits, &sub, &mut search, &mut f);
+        }
+        else {
+            bk_lists(&mut r, p, x, graph, &mut search, &mut f);
+        }
+
+        if search.stopped {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+// Branch-and-bound maximum clique search with greedy coloring bounds (Tomita's MCQ on
+// bitsets, as in San Segundo's BBMC). Returns the largest clique found and whether it is
+// proven to be maximum, which is false only if the time limit is reached.
+pub fn maximum_clique(graph: &CSR, time_limit: Option<Duration>) -> (Vec<usize>, bool){
+    let (cores, order) = kcore::core_numbers_with_order(graph);
+    let rank = permute::order_to_rank(&order);
+    let mut search = Search::new(time_limit);
+
+    let mut best : Vec<usize> = if graph.n_nodes() > 0 { vec![order[0]] } else { vec![] };
+
+    // nodes in higher cores are likely to be in large cliques, so they are tried first.
+    for v in order.iter().rev() {
+        let v = *v;
+        if cores[v] < best.len() {
+            continue;
+        }
+
+        let p : Vec<usize> = graph.neighbors(v).iter().copied().filter(|w| rank[*w] > rank[v]).collect();
+        if p.len() < best.len() {
+            continue;
+        }
+
+        let sub = Subgraph::new(graph, &p);
+        let mut r = vec![];
+        let mut best_local = vec![];
+        // only cliques that, with v, are larger than the best one are of interest
+        let min_size = best.len() - 1;
+        expand(&mut r, sub.to_bits(&p), &sub, min_size, &mut best_local, &mut search);
+
+        if best_local.len() + 1 > best.len() {
+            best = best_local.iter().map(|i| sub.locals[*i]).collect();
+            best.push(v);
+            best.sort_unstable();
+        }
+
+        if search.stopped {
+            return (best, false);
+        }
+    }
+
+    return (best, true);
+}
+
+struct Search{
+    deadline: Option<Instant>,
+    n_calls: usize,
+    stopped: bool
+}
+
+impl Search{
+    fn new(time_limit: Option<Duration>) -> Self{
+        Self {
+            deadline: time_limit.map(|t| Instant::now() + t),
+            n_calls: 0,
+            stopped: false
+        }
+    }
+
+    // checks the clock every 1024 calls.
+    fn timed_out(&mut self) -> bool{
+        self.n_calls += 1;
+        if self.n_calls.is_multiple_of(1024) {
+            if let Some(deadline) = self.deadline {
+                if Instant::now() >= deadline {
+                    self.stopped = true;
+                }
+            }
+        }
+        return self.stopped;
+    }
+}
+
+// The subgraph induced by `locals`, with nodes relabeled to their positions in `locals`.
+struct Subgraph{
+    locals: Vec<usize>,
+    n_blocks: usize,
+    adj: Vec<usize>
+}
+
+impl Subgraph{
+    fn new(graph: &CSR, locals: &[usize]) -> Self{
+        let n = locals.len();
+        let n_blocks = n.div_ceil(BLOCK_SIZE);
+        let mut adj = vec![0usize; n * n_blocks];
+
+        for (i, u) in locals.iter().enumerate() {
+            let row = &mut adj[i * n_blocks..(i + 1) * n_blocks];
+            let mut j = 0usize;
+            for w in graph.neighbors(*u) {
+                while j < n && locals[j] < *w {
+                    j += 1;
+                }
+                if j == n {
+                    break;
+                }
+                if locals[j] == *w {
+                    row[j / BLOCK_SIZE] |= 1 << (j % BLOCK_SIZE);
+                }
+            }
+        }
+
+        return Self { locals: locals.to_vec(), n_blocks, adj };
+    }
+
+    fn row(&self, i: usize) -> &[usize]{
+        return &self.adj[i * self.n_blocks..(i + 1) * self.n_blocks];
+    }
+
+    // nodes must be a sorted subset of locals.
+    fn to_bits(&self, nodes: &[usize]) -> Vec<usize>{
+        let mut bits = vec![0usize; self.n_blocks];
+        let mut j = 0usize;
+        for u in nodes {
+            while self.locals[j] < *u {
+                j += 1;
+            }
+            bits[j / BLOCK_SIZE] |= 1 << (j % BLOCK_SIZE);
+        }
+        return bits;
+    }
+}
+
+fn is_empty(bits: &[usize]) -> bool{
+    return bits.iter().all(|b| *b == 0);
+}
+
+fn count_and(a: &[usize], b: &[usize]) -> usize{
+    return a.iter().zip(b).map(|(x, y)| (x & y).count_ones() as usize).sum();
+}
+
+fn and(a: &[usize], b: &[usize]) -> Vec<usize>{
+    return a.iter().zip(b).map(|(x, y)| x & y).collect();
+}
+
+fn first_bit(bits: &[usize]) -> Option<usize>{
+    for (bid, b) in bits.iter().enumerate() {
+        if *b != 0 {
+            return Some(bid * BLOCK_SIZE + b.trailing_zeros() as usize);
+        }
+    }
+    return None;
+}
+
+fn bk_bits<F: FnMut(&[usize]) -> bool>(r: &mut Vec<usize>, mut p: Vec<usize>, mut x: Vec<usize>, sub: &Subgraph,
+                                       search: &mut Search, f: &mut F){
+    if search.timed_out() {
+        return;
+    }
+
+    if is_empty(&p) {
+        if is_empty(&x) && !f(r) {
+            search.stopped = true;
+        }
+        return;
+    }
+
+    // pivot: the node of P ∪ X with the most neighbors in P
+    let mut pivot = 0usize;
+    let mut max_cnt = 0usize;
+    for bid in 0..sub.n_blocks {
+        let mut bits = p[bid] | x[bid];
+        while bits != 0 {
+            let offset = bits.trailing_zeros() as usize;
+            bits -= 1 << offset;
+            let u = bid * BLOCK_SIZE + offset;
+            let cnt = count_and(&p, sub.row(u));
+            if cnt >= max_cnt {
+                max_cnt = cnt;
+                pivot = u;
+            }
+        }
+    }
+
+    let cands : Vec<usize> = p.iter().zip(sub.row(pivot)).map(|(x, y)| x & !y).collect();
+    for bid in 0..sub.n_blocks {
+        let mut bits = cands[bid];
+        while bits != 0 {
+            let offset = bits.trailing_zeros() as usize;
+            bits -= 1 << offset;
+            let v = bid * BLOCK_SIZE + offset;
+
+            r.push(sub.locals[v]);
+            bk_bits(r, and(&p, sub.row(v)), and(&x, sub.row(v)), sub, search, f);
+            r.pop();
+
+            if search.stopped {
+                return;
+            }
+
+            p[bid] &= !(1 << offset);
+            x[bid] |= 1 << offset;
+        }
+    }
+}
+
+fn bk_lists<F: FnMut(&[usize]) -> bool>(r: &mut Vec<usize>, mut p: Vec<usize>, mut x: Vec<usize>, graph: &CSR,
+                                        search: &mut Search, f: &mut F){
+    if search.timed_out() {
+        return;
+    }
+
+    if p.is_empty() {
+        if x.is_empty() && !f(r) {
+            search.stopped = true;
+        }
+        return;
+    }
+
+    let mut buf = vec![];
+    let mut pivot = p[0];
+    let mut max_cnt = 0usize;
+    for u in p.iter().chain(x.iter()) {
+        intersect_into(&p, graph.neighbors(*u), &mut buf);
+        if buf.len() >= max_cnt {
+            max_cnt = buf.len();
+            pivot = *u;
+        }
+    }
+
+    let pivot_neighbors = graph.neighbors(pivot);
+    let cands : Vec<usize> = p.iter().copied().filter(|v| pivot_neighbors.binary_search(v).is_err()).collect();
+
+    for v in cands {
+        let mut new_p = vec![];
+        let mut new_x = vec![];
+        intersect_into(&p, graph.neighbors(v), &mut new_p);
+        intersect_into(&x, graph.neighbors(v), &mut new_x);
+
+        r.push(v);
+        bk_lists(r, new_p, new_x, graph, search, f);
+        r.pop();
+
+        if search.stopped {
+            return;
+        }
+
+        let i = p.binary_search(&v).unwrap();
+        p.remove(i);
+        let i = x.binary_search(&v).unwrap_err();
+        x.insert(i, v);
+    }
+}
+
+fn expand(r: &mut Vec<usize>, mut p: Vec<usize>, sub: &Subgraph, min_size: usize, best: &mut Vec<usize>,
+          search: &mut Search){
+    if search.timed_out() {
+        return;
+    }
+
+    let (order, colors) = color_sort(&p, sub);
+
+    for i in (0..order.len()).rev() {
+        // the nodes colored so far can extend r by at most colors[i] nodes
+        if r.len() + colors[i] <= min_size.max(best.len()) {
+            return;
+        }
+
+        let v = order[i];
+        let new_p = and(&p, sub.row(v));
+        r.push(v);
+        if is_empty(&new_p) {
+            if r.len() > min_size.max(best.len()) {
+                best.clone_from(r);
+            }
+        }
+        else {
+            expand(r, new_p, sub, min_size, best, search);
+        }
+        r.pop();
+
+        if search.stopped {
+            return;
+        }
+
+        p[v / BLOCK_SIZE] &= !(1 << (v % BLOCK_SIZE));
+    }
+}
+
+// Greedy sequential coloring of P. Nodes are returned in non-decreasing order of their colors.
+fn color_sort(p: &[usize], sub: &Subgraph) -> (Vec<usize>, Vec<usize>){
+    let mut order = vec![];
+    let mut colors = vec![];
+
+    let mut uncolored = p.to_vec();
+    let mut color = 0usize;
+    while !is_empty(&uncolored) {
+        color += 1;
+        let mut q = uncolored.clone();
+        while let Some(v) = first_bit(&q) {
+            uncolored[v / BLOCK_SIZE] &= !(1 << (v % BLOCK_SIZE));
+            q[v / BLOCK_SIZE] &= !(1 << (v % BLOCK_SIZE));
+            for (qb, ab) in q.iter_mut().zip(sub.row(v)) {
+                *qb &= !ab;
+            }
+            order.push(v);
+            colors.push(color);
+        }
+    }
+
+    return (order, colors);
+}
+
+fn merge(a: &[usize], b: &[usize]) -> Vec<usize>{
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    merged.extend_from_slice(a);
+    merged.extend_from_slice(b);
+    merged.sort_unstable();
+    return merged;
+}
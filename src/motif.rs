@@ -0,0 +1,166 @@
+use crate::clique;
+use crate::csr::CSR;
+use crate::kcore;
+use crate::ktruss;
+use crate::tricnt;
+
+// Counts of connected 2- to 4-node graphlets and per-node orbit counts over an undirected CSR
+// with sorted neighbor lists and no self loops (see `CSR::from_edges` and `CSR::sort_neighbors`).
+// Graphlets are induced. Orbits follow Pržulj's numbering:
+//  0: edge
+//  1, 2: path on 3 nodes (end, middle)
+//  3: triangle
+//  4, 5: path on 4 nodes (end, middle)
+//  6, 7: star (leaf, center)
+//  8: cycle
+//  9, 10, 11: tailed triangle (tail end, triangle node of degree 2, triangle node of degree 3)
+//  12, 13: diamond (node of degree 2, node of degree 3)
+//  14: 4-clique
+//
+// Non-induced counts are computed in closed form from degrees and triangles, plus 4-cycles
+// and 4-cliques, and then converted into induced counts as in ORCA (Hočevar & Demšar).
+
+pub const N_ORBITS: usize = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GraphletCounts{
+    pub edges: usize,
+    pub wedges: usize,
+    pub triangles: usize,
+    pub paths: usize,
+    pub stars: usize,
+    pub cycles: usize,
+    pub tailed_triangles: usize,
+    pub diamonds: usize,
+    pub cliques: usize
+}
+
+pub fn count(graph: &CSR) -> GraphletCounts{
+    return graphlet_counts(&orbit_counts(graph));
+}
+
+// Each graphlet is counted once from the nodes in one of its orbits.
+pub fn graphlet_counts(orbits: &[[usize; N_ORBITS]]) -> GraphletCounts{
+    let mut sums = [0usize; N_ORBITS];
+    for o in orbits {
+        for i in 0..N_ORBITS {
+            sums[i] += o[i];
+        }
+    }
+
+    return GraphletCounts{
+        edges: sums[0] / 2,
+        wedges: sums[2],
+        triangles: sums[3] / 3,
+        paths: sums[5] / 2,
+        stars: sums[7],
+        cycles: sums[8] / 4,
+        tailed_triangles: sums[11],
+        diamonds: sums[13] / 2,
+        cliques: sums[14] / 4
+    };
+}
+
+pub fn orbit_counts(graph: &CSR) -> Vec<[usize; N_ORBITS]>{
+    let n_nodes = graph.n_nodes();
+
+    let degrees : Vec<usize> = (0..n_nodes).map(|u| graph.degree(u)).collect();
+    // support[p]: number of triangles containing the edge at position p
+    let support = ktruss::support(graph);
+    let triangles : Vec<usize> = (0..n_nodes)
+        .map(|u| support[graph.nodes[u]..graph.nodes[u+1]].iter().sum::<usize>() / 2)
+        .collect();
+    // sum of (d_w - 1) over the neighbors w of u
+    let wedge_ends : Vec<usize> = (0..n_nodes)
+        .map(|u| graph.neighbors(u).iter().map(|w| degrees[*w] - 1).sum())
+        .collect();
+
+    let mut orbits = vec![[0usize; N_ORBITS]; n_nodes];
+    // non-induced counts of orbits 4 to 14
+    let mut non_induced = vec![[0usize; N_ORBITS]; n_nodes];
+
+    // 4-cliques
+    let (_, order) = kcore::core_numbers_with_order(graph);
+    let oriented = CSR::from_sorted_edges(&kcore::oriented_edges(graph, &order), n_nodes);
+    clique::csr::list(&oriented, 4, |c| {
+        for u in c {
+            non_induced[order[*u]][14] += 1;
+        }
+    });
+
+    let mut common = vec![0usize; n_nodes];
+    let mut touched = vec![];
+
+    for v in 0..n_nodes {
+        let d = degrees[v];
+        let t = triangles[v];
+        let n = &mut non_induced[v];
+
+        orbits[v][0] = d;
+        orbits[v][1] = wedge_ends[v] - 2 * t;
+        orbits[v][2] = choose2(d) - t;
+        orbits[v][3] = t;
+
+        n[7] = choose3(d);
+        n[11] = t * d.saturating_sub(2);
+
+        for p in graph.nodes[v]..graph.nodes[v+1] {
+            let w = graph.edges[p];
+            let s = support[p];
+
+            n[4] += wedge_ends[w] - (d - 1);
+            n[5] += (d - 1) * (degrees[w] - 1) - s;
+            n[6] += choose2(degrees[w] - 1);
+            n[9] += triangles[w] - s;
+            n[13] += choose2(s);
+
+            // triangles (v, w, x) are visited twice, from w and from x
+            tricnt::csr::for_each_intersect(v, w, graph, |_, pw| {
+                n[10] += degrees[w] - 2;
+                n[12] += support[pw] - 1;
+            });
+
+            // 4-cycles v-w-x-y through the wedges v-w-x
+            for x in graph.neighbors(w) {
+                if *x != v {
+                    if common[*x] == 0 {
+                        touched.push(*x);
+                    }
+                    common[*x] += 1;
+                }
+            }
+        }
+
+        n[4] -= 2 * t;
+        n[12] /= 2;
+
+        for x in touched.drain(..) {
+            n[8] += choose2(common[x]);
+            common[x] = 0;
+        }
+
+        // non-induced to induced counts, from the densest graphlet down
+        let o = &mut orbits[v];
+        o[14] = n[14];
+        o[13] = n[13] - 3 * o[14];
+        o[12] = n[12] - 3 * o[14];
+        o[11] = n[11] - 2 * o[13] - 3 * o[14];
+        o[10] = n[10] - 2 * o[12] - 2 * o[13] - 6 * o[14];
+        o[9] = n[9] - 2 * o[12] - 3 * o[14];
+        o[8] = n[8] - o[12] - o[13] - 3 * o[14];
+        o[7] = n[7] - o[11] - o[13] - o[14];
+        o[6] = n[6] - o[9] - o[10] - 2 * o[12] - o[13] - 3 * o[14];
+        o[5] = n[5] - 2 * o[8] - o[10] - 2 * o[11] - 2 * o[12] - 4 * o[13] - 6 * o[14];
+        o[4] = n[4] - 2 * o[8] - 2 * o[9] - o[10] - 4 * o[12] - 2 * o[13] - 6 * o[14];
+    }
+
+    return orbits;
+}
+
+fn choose2(n: usize) -> usize{
+    return n * n.saturating_sub(1) / 2;
+}
+
+fn choose3(n: usize) -> usize{
+    return n * n.saturating_sub(1) * n.saturating_sub(2) / 6;
+}
@@ -0,0 +1,184 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossbeam;
+
+use crate::csr::CSR;
+
+// Relabeling of graphs by a node order, as returned by `slashburn::slashburn` or
+// `kcore::core_numbers_with_order`: order[i] is the old id of the node whose new id is i.
+// See also `CSR::permute` and `CSBV::permute`.
+
+pub fn is_permutation(order: &[usize], n_nodes: usize) -> bool{
+    if order.len() != n_nodes {
+        return false;
+    }
+    let mut seen = vec![false; n_nodes];
+    for u in order {
+        if *u >= n_nodes || seen[*u] {
+            return false;
+        }
+        seen[*u] = true;
+    }
+    return true;
+}
+
+pub fn check_permutation(order: &[usize], n_nodes: usize){
+    assert!(is_permutation(order, n_nodes), "the order must be a permutation of the {} nodes", n_nodes);
+}
+
+// Inverts an order: rank[u] is the new id of node u.
+pub fn order_to_rank(order: &[usize]) -> Vec<usize>{
+    let mut rank = vec![0usize; order.len()];
+    for (i, u) in order.iter().enumerate() {
+        rank[*u] = i;
+    }
+    return rank;
+}
+
+// Relabeled edges, sorted.
+pub fn permute_edges(edges: &[(usize, usize)], order: &[usize], n_thread: usize) -> Vec<(usize, usize)>{
+    return relabel_edges(edges, order, n_thread, false);
+}
+
+// Relabeled edges oriented from the lower to the higher new id, sorted, without duplicates
+// nor self loops, e.g. for `CSR::from_sorted_edges` and `CSBV::from_sorted_edges`.
+pub fn permute_edges_oriented(edges: &[(usize, usize)], order: &[usize], n_thread: usize) -> Vec<(usize, usize)>{
+    return relabel_edges(edges, order, n_thread, true);
+}
+
+fn relabel_edges(edges: &[(usize, usize)], order: &[usize], n_thread: usize, oriented: bool) -> Vec<(usize, usize)>{
+    let n_nodes = order.len();
+    check_permutation(order, n_nodes);
+    let rank = order_to_rank(order);
+
+    let csr = csr_from_pairs(edges.len(), n_nodes, oriented, n_thread, |i, push| {
+        let (u, v) = (rank[edges[i].0], rank[edges[i].1]);
+        if !oriented {
+            push(u, v);
+        }
+        else if u != v {
+            push(u.min(v), u.max(v));
+        }
+    });
+    return csr.iter_edges().collect();
+}
+
+// Builds a CSR from the pairs that pairs(i, push) pushes for every source i in 0..n_sources, sources
+// being split over threads. Neighbor lists are sorted, and duplicates are dropped if dedup.
+// pairs is called twice per source, first to count the pairs and then to place them, so it must
+// push the same pairs both times.
+pub fn csr_from_pairs<F>(n_sources: usize, n_nodes: usize, dedup: bool, n_thread: usize, pairs: F) -> CSR
+    where F: Fn(usize, &mut dyn FnMut(usize, usize)) + Sync{
+    let n_thread = n_thread.max(1);
+    let chunk_size = n_sources.div_ceil(n_thread).max(1);
+    let pairs = &pairs;
+
+    let counts : Vec<AtomicUsize> = (0..n_nodes).map(|_| AtomicUsize::new(0)).collect();
+    crossbeam::scope(|scope| {
+        for start in (0..n_sources).step_by(chunk_size) {
+            let counts = &counts;
+            scope.spawn(move |_| {
+                for i in start..(start + chunk_size).min(n_sources) {
+                    pairs(i, &mut |u, _| { counts[u].fetch_add(1, Ordering::Relaxed); });
+                }
+            });
+        }
+    }).unwrap();
+
+    let mut nodes = vec![0usize; n_nodes + 1];
+    for u in 0..n_nodes {
+        nodes[u+1] = nodes[u] + counts[u].load(Ordering::Relaxed);
+    }
+
+    // the cursor of each list starts at its offset
+    let cursors : Vec<AtomicUsize> = nodes[..n_nodes].iter().map(|p| AtomicUsize::new(*p)).collect();
+    let edges : Vec<AtomicUsize> = (0..nodes[n_nodes]).map(|_| AtomicUsize::new(0)).collect();
+    crossbeam::scope(|scope| {
+        for start in (0..n_sources).step_by(chunk_size) {
+            let (cursors, edges) = (&cursors, &edges);
+            scope.spawn(move |_| {
+                for i in start..(start + chunk_size).min(n_sources) {
+                    pairs(i, &mut |u, v| {
+                        let p = cursors[u].fetch_add(1, Ordering::Relaxed);
+                        edges[p].store(v, Ordering::Relaxed);
+                    });
+                }
+            });
+        }
+    }).unwrap();
+
+    let mut csr = CSR{
+        nodes,
+        edges: edges.into_iter().map(|x| x.into_inner()).collect()
+    };
+
+    // lists are placed in any order, so each is sorted
+    let mut lengths = vec![0usize; n_nodes];
+    crossbeam::scope(|scope| {
+        let mut rest = &mut csr.edges[..];
+        let mut lengths_rest = &mut lengths[..];
+        for (first, last) in balanced_ranges(&csr.nodes, n_thread) {
+            let (list_chunk, tail) = rest.split_at_mut(csr.nodes[last] - csr.nodes[first]);
+            let (length_chunk, lengths_tail) = lengths_rest.split_at_mut(last - first);
+            rest = tail;
+            lengths_rest = lengths_tail;
+
+            let nodes = &csr.nodes;
+            scope.spawn(move |_| {
+                for u in first..last {
+                    let list = &mut list_chunk[nodes[u] - nodes[first]..nodes[u+1] - nodes[first]];
+                    list.sort_unstable();
+                    let mut len = list.len();
+                    if dedup && len > 0 {
+                        len = 1;
+                        for j in 1..list.len() {
+                            if list[j] != list[len-1] {
+                                list[len] = list[j];
+                                len += 1;
+                            }
+                        }
+                    }
+                    length_chunk[u - first] = len;
+                }
+            });
+        }
+    }).unwrap();
+
+    if dedup {
+        let mut p = 0usize;
+        for u in 0..n_nodes {
+            let start = csr.nodes[u];
+            csr.edges.copy_within(start..start + lengths[u], p);
+            csr.nodes[u] = p;
+            p += lengths[u];
+        }
+        csr.nodes[n_nodes] = p;
+        csr.edges.truncate(p);
+    }
+
+    return csr;
+}
+
+// Splits the nodes into at most n_parts ranges (first, last) of consecutive nodes with about as
+// many edges each.
+pub fn balanced_ranges(nodes: &[usize], n_parts: usize) -> Vec<(usize, usize)>{
+    let n_nodes = nodes.len() - 1;
+    let n_edges = nodes[n_nodes];
+    let n_parts = n_parts.max(1);
+
+    let mut ranges = vec![];
+    let mut first = 0usize;
+    for t in 1..=n_parts {
+        let last = if t == n_parts {
+            n_nodes
+        }
+        else {
+            nodes.partition_point(|p| *p < n_edges * t / n_parts).clamp(first, n_nodes)
+        };
+        if last > first {
+            ranges.push((first, last));
+            first = last;
+        }
+    }
+    return ranges;
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+use crossbeam;
+
+use crate::csr::CSR;
+
+// PageRank over a CSR holding the out-neighbors of each node. For undirected graphs
+// built by `CSR::from_edges`, every edge is followed in both directions.
+// The rank mass of dangling nodes (without out-edges) is redistributed by the teleport
+// distribution, so scores always sum to 1.
+
+pub fn pagerank(graph: &CSR, damping: f64, tolerance: f64, max_iter: usize) -> Vec<f64>{
+    let teleport = vec![1.0 / graph.n_nodes() as f64; graph.n_nodes()];
+    return power_iteration(graph, &teleport, damping, tolerance, max_iter, 1);
+}
+
+pub fn pagerank_parallel(graph: &CSR, damping: f64, tolerance: f64, max_iter: usize, n_thread: usize) -> Vec<f64>{
+    let teleport = vec![1.0 / graph.n_nodes() as f64; graph.n_nodes()];
+    return power_iteration(graph, &teleport, damping, tolerance, max_iter, n_thread);
+}
+
+// PageRank whose random jumps go to the seed nodes only.
+pub fn personalized_pagerank(graph: &CSR, seeds: &[usize], damping: f64, tolerance: f64, max_iter: usize) -> Vec<f64>{
+    let teleport = seed_distribution(graph.n_nodes(), seeds);
+    return power_iteration(graph, &teleport, damping, tolerance, max_iter, 1);
+}
+
+pub fn personalized_pagerank_parallel(graph: &CSR, seeds: &[usize], damping: f64, tolerance: f64, max_iter: usize,
+                                      n_thread: usize) -> Vec<f64>{
+    let teleport = seed_distribution(graph.n_nodes(), seeds);
+    return power_iteration(graph, &teleport, damping, tolerance, max_iter, n_thread);
+}
+
+fn seed_distribution(n_nodes: usize, seeds: &[usize]) -> Vec<f64>{
+    assert!(!seeds.is_empty(), "at least one seed is required");
+
+    let mut teleport = vec![0f64; n_nodes];
+    for s in seeds {
+        teleport[*s] += 1.0 / seeds.len() as f64;
+    }
+    return teleport;
+}
+
+// Pull-based power iteration: each node gathers the rank of its in-neighbors, so threads
+// write disjoint ranges of the new scores. Stops when the L1 change drops below the tolerance.
+fn power_iteration(graph: &CSR, teleport: &[f64], damping: f64, tolerance: f64, max_iter: usize,
+                   n_thread: usize) -> Vec<f64>{
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let chunk_size = n_nodes.div_ceil(n_thread).max(1);
+
+    let in_graph = graph.transpose();
+    let inv_degrees : Vec<f64> = (0..n_nodes)
+        .map(|u| if graph.degree(u) > 0 { 1.0 / graph.degree(u) as f64 } else { 0.0 })
+        .collect();
+
+    let mut ranks = teleport.to_vec();
+    let mut next_ranks = vec![0f64; n_nodes];
+
+    for _ in 0..max_iter {
+        let dangling : f64 = (0..n_nodes).filter(|u| graph.degree(*u) == 0).map(|u| ranks[u]).sum();
+
+        let mut diff = 0f64;
+        crossbeam::scope(|scope| {
+            let mut threads = vec![];
+            for (i, chunk) in next_ranks.chunks_mut(chunk_size).enumerate() {
+                let (in_graph, inv_degrees, ranks) = (&in_graph, &inv_degrees, &ranks);
+                threads.push(scope.spawn(move |_| -> f64 {
+                    let mut diff = 0f64;
+                    for (j, next) in chunk.iter_mut().enumerate() {
+                        let v = i * chunk_size + j;
+                        let mut sum = 0f64;
+                        for u in in_graph.neighbors(v) {
+                            sum += ranks[*u] * inv_degrees[*u];
+                        }
+                        *next = damping * (sum + dangling * teleport[v]) + (1.0 - damping) * teleport[v];
+                        diff += (*next - ranks[v]).abs();
+                    }
+                    return diff;
+                }));
+            }
+
+            for t in threads {
+                diff += t.join().unwrap();
+            }
+        }).unwrap();
+
+        std::mem::swap(&mut ranks, &mut next_ranks);
+
+        if diff < tolerance {
+            break;
+        }
+    }
+
+    return ranks;
+}
+
+// Approximate personalized PageRank by local pushes (Andersen, Chung & Lang).
+// Pushes stop once every residual is at most epsilon times the node's degree, so the work
+// depends on epsilon and the neighborhood of the seeds rather than on the graph size.
+pub fn local_ppr(graph: &CSR, seeds: &[usize], damping: f64, epsilon: f64) -> Vec<f64>{
+    let n_nodes = graph.n_nodes();
+    let teleport = seed_distribution(n_nodes, seeds);
+    let mut seed_nodes = seeds.to_vec();
+    seed_nodes.sort_unstable();
+    seed_nodes.dedup();
+
+    let mut ranks = vec![0f64; n_nodes];
+    let mut residuals = vec![0f64; n_nodes];
+    let mut in_queue = vec![false; n_nodes];
+    let mut queue = VecDeque::new();
+
+    for s in &seed_nodes {
+        add_residual(*s, teleport[*s], graph, epsilon, &mut residuals, &mut in_queue, &mut queue);
+    }
+
+    while let Some(u) = queue.pop_front() {
+        in_queue[u] = false;
+
+        let r = residuals[u];
+        residuals[u] = 0.0;
+        ranks[u] += (1.0 - damping) * r;
+
+        if graph.degree(u) > 0 {
+            let share = damping * r / graph.degree(u) as f64;
+            for v in graph.neighbors(u) {
+                add_residual(*v, share, graph, epsilon, &mut residuals, &mut in_queue, &mut queue);
+            }
+        }
+        else {
+            // dangling nodes jump back to the seeds
+            for v in &seed_nodes {
+                add_residual(*v, damping * r * teleport[*v], graph, epsilon, &mut residuals, &mut in_queue, &mut queue);
+            }
+        }
+    }
+
+    return ranks;
+}
+
+fn add_residual(v: usize, r: f64, graph: &CSR, epsilon: f64, residuals: &mut [f64], in_queue: &mut [bool],
+                queue: &mut VecDeque<usize>){
+    residuals[v] += r;
+    if !in_queue[v] && residuals[v] > epsilon * graph.degree(v).max(1) as f64 {
+        in_queue[v] = true;
+        queue.push_back(v);
+    }
+}
@@ -0,0 +1,417 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use crate::csbv::CSBV;
+use crate::csr::CSR;
+use crate::rng::Rng;
+use crate::vis;
+
+// Node orderings of an undirected CSR (both directions of each edge are stored, e.g. built by
+// `CSR::from_edges`), in the convention of `slashburn::slashburn`: order[i] is the node placed
+// at position i. Apply them with `CSR::permute`.
+
+// Nodes by decreasing degree, ties going to the smaller id.
+pub fn degree_sort(graph: &CSR) -> Vec<usize>{
+    let mut order : Vec<usize> = (0..graph.n_nodes()).collect();
+    order.sort_by_key(|u| Reverse(graph.degree(*u)));
+    return order;
+}
+
+// Uniform random order, the baseline of no locality.
+pub fn random(n_nodes: usize, seed: u64) -> Vec<usize>{
+    let mut order : Vec<usize> = (0..n_nodes).collect();
+    let mut rng = Rng::new(seed);
+    for i in (1..n_nodes).rev() {
+        order.swap(i, rng.gen_range(i + 1));
+    }
+    return order;
+}
+
+// BFS order, each component starting from its smallest node and neighbors taken in list order.
+pub fn bfs(graph: &CSR) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let mut visited = vec![false; n_nodes];
+    let mut order = Vec::with_capacity(n_nodes);
+
+    for s in 0..n_nodes {
+        if visited[s] {
+            continue;
+        }
+        visited[s] = true;
+        let first = order.len();
+        order.push(s);
+        let mut i = first;
+        while i < order.len() {
+            let u = order[i];
+            i += 1;
+            for v in graph.neighbors(u) {
+                if !visited[*v] {
+                    visited[*v] = true;
+                    order.push(*v);
+                }
+            }
+        }
+    }
+    return order;
+}
+
+// Reverse Cuthill–McKee: BFS from a pseudo-peripheral node of each component, visiting the
+// neighbors of each node by increasing degree, then reversed. Keeps nonzeros close to the
+// diagonal, i.e. reduces the bandwidth and profile of the adjacency matrix.
+pub fn rcm(graph: &CSR) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let mut visited = vec![false; n_nodes];
+    let mut levels = vec![usize::MAX; n_nodes];
+    let mut order = Vec::with_capacity(n_nodes);
+    let mut neighbors = vec![];
+
+    for s in 0..n_nodes {
+        if visited[s] {
+            continue;
+        }
+
+        let start = pseudo_peripheral(graph, s, &mut levels);
+        visited[start] = true;
+        let first = order.len();
+        order.push(start);
+        let mut i = first;
+        while i < order.len() {
+            let u = order[i];
+            i += 1;
+            neighbors.clear();
+            neighbors.extend(graph.neighbors(u).iter().copied().filter(|v| !visited[*v]));
+            neighbors.sort_unstable_by_key(|v| (graph.degree(*v), *v));
+            neighbors.dedup();
+            for v in &neighbors {
+                visited[*v] = true;
+                order.push(*v);
+            }
+        }
+    }
+
+    order.reverse();
+    return order;
+}
+
+// George & Liu: repeatedly moves to a node of smallest degree in the last BFS level while the
+// eccentricity grows.
+fn pseudo_peripheral(graph: &CSR, s: usize, levels: &mut [usize]) -> usize{
+    let mut queue = VecDeque::new();
+    let mut component = vec![];
+    let mut u = s;
+    let mut ecc = 0usize;
+
+    loop {
+        for x in component.drain(..) {
+            levels[x] = usize::MAX;
+        }
+        levels[u] = 0;
+        queue.push_back(u);
+        while let Some(x) = queue.pop_front() {
+            component.push(x);
+            for y in graph.neighbors(x) {
+                if levels[*y] == usize::MAX {
+                    levels[*y] = levels[x] + 1;
+                    queue.push_back(*y);
+                }
+            }
+        }
+
+        let depth = levels[*component.last().unwrap()];
+        let next = component.iter().copied().filter(|x| levels[*x] == depth)
+            .min_by_key(|x| (graph.degree(*x), *x)).unwrap();
+        if depth <= ecc && ecc > 0 {
+            break;
+        }
+        ecc = depth;
+        u = next;
+        if depth == 0 {
+            break;
+        }
+    }
+
+    for x in component.drain(..) {
+        levels[x] = usize::MAX;
+    }
+    return u;
+}
+
+// Gorder (Wei et al.): greedily appends the node with the largest score against the last
+// `window` placed nodes, the score of v counting the neighbors of v in the window and the
+// common neighbors of v with each of them. Starts from a node of largest degree.
+// As in the original implementation, scores are kept in a unit heap, so that each update costs
+// O(1), and nodes of degree above sqrt(n) do not propagate common-neighbor scores, which would
+// cost O(degree^2) per placement. Among equal scores the last updated node goes first.
+pub fn gorder(graph: &CSR, window: usize) -> Vec<usize>{
+    assert!(window > 0, "window must be positive");
+
+    let n_nodes = graph.n_nodes();
+    let hub_degree = (n_nodes as f64).sqrt().ceil() as usize;
+    let mut heap = UnitHeap::new(n_nodes);
+    let mut placed = vec![false; n_nodes];
+    let mut order = Vec::with_capacity(n_nodes);
+
+    // raises or lowers by one the score of each node related to v
+    let update = |v: usize, add: bool, heap: &mut UnitHeap, placed: &[bool]| {
+        let mut change = |x: usize| {
+            if placed[x] {
+                return;
+            }
+            if add {
+                heap.increment(x);
+            }
+            else {
+                heap.decrement(x);
+            }
+        };
+
+        for u in graph.neighbors(v) {
+            change(*u);
+            if graph.degree(*u) <= hub_degree {
+                for w in graph.neighbors(*u) {
+                    if *w != v {
+                        change(*w);
+                    }
+                }
+            }
+        }
+    };
+
+    let mut next = (0..n_nodes).max_by_key(|u| (graph.degree(*u), Reverse(*u)));
+    if let Some(v) = next {
+        heap.remove(v);
+    }
+    while let Some(v) = next {
+        placed[v] = true;
+        order.push(v);
+        update(v, true, &mut heap, &placed);
+        if order.len() > window {
+            let old = order[order.len() - window - 1];
+            update(old, false, &mut heap, &placed);
+        }
+        next = heap.pop_max();
+    }
+
+    return order;
+}
+
+const NONE: usize = usize::MAX;
+
+// Nodes bucketed by score in doubly linked lists, for O(1) increments and decrements and an
+// amortized O(1) extraction of a node of largest score.
+struct UnitHeap{
+    scores: Vec<usize>,
+    // first node of each score
+    heads: Vec<usize>,
+    prev: Vec<usize>,
+    next: Vec<usize>,
+    // no score above top is used
+    top: usize
+}
+
+impl UnitHeap{
+
+    // every node with score 0, the smallest id first.
+    fn new(n_nodes: usize) -> Self{
+        let mut heap = UnitHeap{
+            scores: vec![0; n_nodes],
+            heads: vec![NONE],
+            prev: vec![NONE; n_nodes],
+            next: vec![NONE; n_nodes],
+            top: 0
+        };
+        for u in (0..n_nodes).rev() {
+            heap.push_front(u);
+        }
+        return heap;
+    }
+
+    fn push_front(&mut self, u: usize){
+        let score = self.scores[u];
+        if score == self.heads.len() {
+            self.heads.push(NONE);
+        }
+        let head = self.heads[score];
+        self.prev[u] = NONE;
+        self.next[u] = head;
+        if head != NONE {
+            self.prev[head] = u;
+        }
+        self.heads[score] = u;
+        self.top = self.top.max(score);
+    }
+
+    fn remove(&mut self, u: usize){
+        let (prev, next) = (self.prev[u], self.next[u]);
+        if prev != NONE {
+            self.next[prev] = next;
+        }
+        else {
+            let score = self.scores[u];
+            self.heads[score] = next;
+        }
+        if next != NONE {
+            self.prev[next] = prev;
+        }
+    }
+
+    fn increment(&mut self, u: usize){
+        self.remove(u);
+        self.scores[u] += 1;
+        self.push_front(u);
+    }
+
+    fn decrement(&mut self, u: usize){
+        self.remove(u);
+        self.scores[u] -= 1;
+        self.push_front(u);
+    }
+
+    fn pop_max(&mut self) -> Option<usize>{
+        while self.top > 0 && self.heads[self.top] == NONE {
+            self.top -= 1;
+        }
+        let u = self.heads[self.top];
+        if u == NONE {
+            return None;
+        }
+        self.remove(u);
+        return Some(u);
+    }
+}
+
+// Rabbit-order-style community ordering (Arai et al.): nodes, by increasing degree, merge into
+// the neighbor community of largest positive modularity gain, building a dendrogram.
+// The order lists each dendrogram depth first, so that nested communities get consecutive ids.
+pub fn rabbit_order(graph: &CSR) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let two_m = graph.edges.len() as f64;
+
+    // weighted edges of each community, with endpoints that may have been merged since
+    let mut adjacency : Vec<Vec<(usize, f64)>> = (0..n_nodes)
+        .map(|u| graph.neighbors(u).iter().filter(|v| **v != u).map(|v| (*v, 1.0)).collect())
+        .collect();
+    let mut degrees : Vec<f64> = (0..n_nodes).map(|u| graph.degree(u) as f64).collect();
+    let mut parents : Vec<usize> = (0..n_nodes).collect();
+    let mut children : Vec<Vec<usize>> = vec![vec![]; n_nodes];
+
+    let mut nodes : Vec<usize> = (0..n_nodes).collect();
+    nodes.sort_by_key(|u| graph.degree(*u));
+
+    let mut weights = vec![0f64; n_nodes];
+    let mut touched = vec![];
+    for u in nodes {
+        // gather the weights to each neighbor community
+        let list = std::mem::take(&mut adjacency[u]);
+        for (v, w) in list {
+            let c = find(&mut parents, v);
+            if c != u {
+                if weights[c] == 0.0 {
+                    touched.push(c);
+                }
+                weights[c] += w;
+            }
+        }
+
+        let mut best = None;
+        let mut best_gain = 0f64;
+        for c in &touched {
+            let gain = weights[*c] / two_m - degrees[u] * degrees[*c] / (two_m * two_m);
+            if gain > best_gain {
+                best = Some(*c);
+                best_gain = gain;
+            }
+        }
+
+        let merged : Vec<(usize, f64)> = touched.drain(..).map(|c| (c, std::mem::take(&mut weights[c]))).collect();
+        match best {
+            Some(c) => {
+                parents[u] = c;
+                children[c].push(u);
+                degrees[c] += degrees[u];
+                adjacency[c].extend(merged);
+            }
+            None => adjacency[u] = merged
+        }
+    }
+
+    let mut order = Vec::with_capacity(n_nodes);
+    let mut stack = vec![];
+    for r in 0..n_nodes {
+        if parents[r] != r {
+            continue;
+        }
+        stack.push(r);
+        while let Some(u) = stack.pop() {
+            order.push(u);
+            stack.extend(children[u].iter().rev());
+        }
+    }
+    return order;
+}
+
+fn find(parents: &mut [usize], mut u: usize) -> usize{
+    while parents[u] != u {
+        parents[u] = parents[parents[u]];
+        u = parents[u];
+    }
+    return u;
+}
+
+// Locality and compressibility of a graph relabeled by an order, to compare orderings.
+pub struct OrderMetrics{
+    // bit blocks of the CSBV of the graph oriented from lower to higher ids, as used by
+    // `tricnt::csbv` and `clique::csbv`
+    pub n_blocks: usize,
+    pub bits_per_block: f64,
+    // mean of log2(1 + gap) over the gaps between a node and its first neighbor and between
+    // consecutive neighbors, i.e. the bits per edge of a gap encoding
+    pub log_gap: f64,
+    // largest |u - v| over the edges
+    pub bandwidth: usize,
+    // sum over nodes u of u minus the smallest neighbor below u
+    pub profile: usize,
+    // mean |u - v| over the edges
+    pub mean_gap: f64,
+    // non-empty cells of `vis::scaling` with n_pixels pixels per side
+    pub n_cells: usize
+}
+
+pub fn evaluate(graph: &CSR, order: &[usize], n_pixels: usize) -> OrderMetrics{
+    let n_nodes = graph.n_nodes();
+    let permuted = graph.permute(order, 1);
+    let edges : Vec<(usize, usize)> = permuted.iter_edges().collect();
+    let oriented : Vec<(usize, usize)> = edges.iter().copied().filter(|(u, v)| u < v).collect();
+
+    let csbv = CSBV::from_sorted_edges(&oriented, n_nodes);
+    let n_bits : usize = csbv.bit_blocks.iter().map(|b| b.count_ones() as usize).sum();
+
+    let mut log_gap = 0f64;
+    let mut bandwidth = 0usize;
+    let mut profile = 0usize;
+    let mut sum_gaps = 0usize;
+    for u in 0..n_nodes {
+        let neighbors = permuted.neighbors(u);
+        let mut prev = u;
+        for v in neighbors {
+            log_gap += ((prev.abs_diff(*v) + 1) as f64).log2();
+            prev = *v;
+            bandwidth = bandwidth.max(u.abs_diff(*v));
+            sum_gaps += u.abs_diff(*v);
+        }
+        if let Some(first) = neighbors.first() {
+            profile += u.saturating_sub(*first);
+        }
+    }
+
+    let mean = |x: f64, n: usize| if n > 0 { x / n as f64 } else { 0.0 };
+    return OrderMetrics{
+        n_blocks: csbv.block_ids.len(),
+        bits_per_block: mean(n_bits as f64, csbv.block_ids.len()),
+        log_gap: mean(log_gap, edges.len()),
+        bandwidth,
+        profile,
+        mean_gap: mean(sum_gaps as f64, edges.len()),
+        n_cells: if n_pixels > 0 { vis::scaling(&edges, n_nodes, n_pixels).len() } else { 0 }
+    };
+}
@@ -0,0 +1,30 @@
+// SplitMix64 (Steele, Lea & Flood), a small seeded generator for the randomized algorithms.
+// Generators seeded with consecutive seeds produce unrelated streams.
+#[derive(Clone)]
+pub struct Rng{
+    state: u64
+}
+
+impl Rng{
+    pub fn new(seed: u64) -> Self{
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64{
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    // uniform in 0..n by Lemire's multiply-shift.
+    pub fn gen_range(&mut self, n: usize) -> usize{
+        return ((self.next_u64() as u128 * n as u128) >> 64) as usize;
+    }
+
+    // uniform in [0, 1).
+    pub fn gen_f64(&mut self) -> f64{
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+}
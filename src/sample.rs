@@ -0,0 +1,210 @@
+use crate::csr::CSR;
+use crate::rng::Rng;
+use crate::view::induced_subgraph;
+
+// Samplers building representative subgraphs of a CSR holding the out-neighbors of each node.
+// Each sampler selects up to n_samples nodes and returns the subgraph they induce, relabeled
+// as by `view::induced_subgraph`, with the original id of each new node.
+// Walks and fires that cannot reach new nodes jump to a random node that was not selected yet.
+
+// Selected nodes, in selection order.
+struct Selection{
+    selected: Vec<bool>,
+    nodes: Vec<usize>
+}
+
+impl Selection{
+
+    fn new(n_nodes: usize) -> Self{
+        Self { selected: vec![false; n_nodes], nodes: vec![] }
+    }
+
+    fn insert(&mut self, u: usize) -> bool{
+        if self.selected[u] {
+            return false;
+        }
+        self.selected[u] = true;
+        self.nodes.push(u);
+        return true;
+    }
+
+    fn len(&self) -> usize{
+        return self.nodes.len();
+    }
+
+    // a uniform node that is not selected yet, by rejection. Some node must be left.
+    fn random_unselected(&self, rng: &mut Rng) -> usize{
+        loop {
+            let u = rng.gen_range(self.selected.len());
+            if !self.selected[u] {
+                return u;
+            }
+        }
+    }
+
+    // up to k neighbors of u that are not selected yet, chosen uniformly, in random order.
+    fn pick_neighbors(&self, graph: &CSR, u: usize, k: usize, rng: &mut Rng) -> Vec<usize>{
+        let mut candidates : Vec<usize> = graph.neighbors(u).iter().copied().filter(|v| !self.selected[*v]).collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        let k = k.min(candidates.len());
+        for i in 0..k {
+            let j = i + rng.gen_range(candidates.len() - i);
+            candidates.swap(i, j);
+        }
+        candidates.truncate(k);
+        return candidates;
+    }
+}
+
+// Uniform node sampling.
+pub fn node_sample(graph: &CSR, n_samples: usize, seed: u64) -> (CSR, Vec<usize>){
+    let n_nodes = graph.n_nodes();
+    let k = n_samples.min(n_nodes);
+    let mut rng = Rng::new(seed);
+
+    // partial Fisher–Yates shuffle
+    let mut nodes : Vec<usize> = (0..n_nodes).collect();
+    for i in 0..k {
+        let j = i + rng.gen_range(n_nodes - i);
+        nodes.swap(i, j);
+    }
+    nodes.truncate(k);
+
+    return induced_subgraph(graph, &nodes);
+}
+
+// Induced edge sampling (Ahmed, Neville & Kompella): edges are drawn uniformly and their
+// endpoints selected until n_samples nodes are, then the subgraph they induce is returned.
+pub fn edge_sample(graph: &CSR, n_samples: usize, seed: u64) -> (CSR, Vec<usize>){
+    let n_nodes = graph.n_nodes();
+    let mut rng = Rng::new(seed);
+
+    // only the endpoints of some edge can be selected
+    let mut is_endpoint = vec![false; n_nodes];
+    for (u, v) in graph.iter_edges() {
+        is_endpoint[u] = true;
+        is_endpoint[v] = true;
+    }
+    let k = n_samples.min(is_endpoint.iter().filter(|x| **x).count());
+
+    let mut selection = Selection::new(n_nodes);
+    while selection.len() < k {
+        let p = rng.gen_range(graph.edges.len());
+        let u = graph.nodes.partition_point(|x| *x <= p) - 1;
+        selection.insert(u);
+        if selection.len() < k {
+            selection.insert(graph.edges[p]);
+        }
+    }
+
+    return induced_subgraph(graph, &selection.nodes);
+}
+
+// Random walk with restart: at each step the walk goes back to its start node with probability
+// restart, and otherwise moves to a uniform neighbor. The walk jumps to a new start node at dead
+// ends and when it found no new node within n_nodes steps.
+pub fn random_walk(graph: &CSR, n_samples: usize, restart: f64, seed: u64) -> (CSR, Vec<usize>){
+    return walk(graph, n_samples, seed, |u, rng| {
+        let v = graph.neighbors(u)[rng.gen_range(graph.degree(u))];
+        if rng.gen_f64() < restart { None } else { Some(v) }
+    });
+}
+
+// Metropolis–Hastings random walk: a move from u to a uniform neighbor v is accepted with
+// probability min(1, deg(u) / deg(v)), which makes the stationary distribution uniform over the
+// nodes of an undirected graph instead of biased towards high degrees.
+pub fn metropolis_hastings_walk(graph: &CSR, n_samples: usize, seed: u64) -> (CSR, Vec<usize>){
+    return walk(graph, n_samples, seed, |u, rng| {
+        let v = graph.neighbors(u)[rng.gen_range(graph.degree(u))];
+        let accept = graph.degree(u) as f64 / graph.degree(v).max(1) as f64;
+        if rng.gen_f64() < accept { Some(v) } else { Some(u) }
+    });
+}
+
+// Runs a walk whose step, from a node with neighbors, returns the next node or None to restart.
+fn walk<F: FnMut(usize, &mut Rng) -> Option<usize>>(graph: &CSR, n_samples: usize, seed: u64, mut step: F)
+                                                    -> (CSR, Vec<usize>){
+    let n_nodes = graph.n_nodes();
+    let k = n_samples.min(n_nodes);
+    let mut rng = Rng::new(seed);
+    let mut selection = Selection::new(n_nodes);
+
+    if k == 0 {
+        return induced_subgraph(graph, &[]);
+    }
+
+    let mut start = rng.gen_range(n_nodes);
+    let mut u = start;
+    let mut idle = 0usize;
+    selection.insert(u);
+
+    while selection.len() < k {
+        if graph.degree(u) == 0 || idle >= n_nodes {
+            start = selection.random_unselected(&mut rng);
+            u = start;
+            idle = 0;
+            selection.insert(u);
+            continue;
+        }
+
+        u = step(u, &mut rng).unwrap_or(start);
+        if selection.insert(u) {
+            idle = 0;
+        }
+        else {
+            idle += 1;
+        }
+    }
+
+    return induced_subgraph(graph, &selection.nodes);
+}
+
+// Forest fire (Leskovec & Faloutsos): each burning node sets fire to a geometric number of its
+// unburnt neighbors, with mean p_forward / (1 - p_forward), which burn in turn in BFS order.
+pub fn forest_fire(graph: &CSR, n_samples: usize, p_forward: f64, seed: u64) -> (CSR, Vec<usize>){
+    assert!((0.0..1.0).contains(&p_forward), "p_forward must be in [0, 1)");
+
+    return spread(graph, n_samples, seed, |rng| {
+        let mut k = 0usize;
+        while rng.gen_f64() < p_forward {
+            k += 1;
+        }
+        k
+    });
+}
+
+// Snowball sampling: BFS where each node adds at most max_neighbors random unselected neighbors.
+pub fn snowball(graph: &CSR, n_samples: usize, max_neighbors: usize, seed: u64) -> (CSR, Vec<usize>){
+    return spread(graph, n_samples, seed, |_| max_neighbors);
+}
+
+// BFS from random nodes, where each node selects a number of its neighbors given by n_picks.
+fn spread<F: FnMut(&mut Rng) -> usize>(graph: &CSR, n_samples: usize, seed: u64, mut n_picks: F) -> (CSR, Vec<usize>){
+    let n_nodes = graph.n_nodes();
+    let k = n_samples.min(n_nodes);
+    let mut rng = Rng::new(seed);
+    let mut selection = Selection::new(n_nodes);
+    let mut next = 0usize;
+
+    while selection.len() < k {
+        // the nodes selected since the last restart form the queue
+        if next == selection.len() {
+            let u = selection.random_unselected(&mut rng);
+            selection.insert(u);
+            continue;
+        }
+
+        let u = selection.nodes[next];
+        next += 1;
+        let n = n_picks(&mut rng);
+        for v in selection.pick_neighbors(graph, u, n, &mut rng) {
+            if selection.len() == k {
+                break;
+            }
+            selection.insert(v);
+        }
+    }
+
+    return induced_subgraph(graph, &selection.nodes);
+}
@@ -0,0 +1,229 @@
+use crossbeam;
+
+use crate::heap::IndexedHeap;
+use crate::wcsr::WCSR;
+
+// Shortest paths over a weighted CSR holding the out-edges of each node.
+// Every single-source algorithm returns the distances, f64::INFINITY for unreachable nodes, and the
+// predecessor of each node on a shortest path, usize::MAX for the source and unreachable nodes.
+
+pub const NO_PRED: usize = usize::MAX;
+
+// Dijkstra's algorithm with an indexed heap. Weights must be non-negative.
+pub fn dijkstra(graph: &WCSR, source: usize) -> (Vec<f64>, Vec<usize>){
+    let n_nodes = graph.n_nodes();
+    let mut dist = vec![f64::INFINITY; n_nodes];
+    let mut pred = vec![NO_PRED; n_nodes];
+    let mut heap : IndexedHeap<f64> = IndexedHeap::new(n_nodes);
+
+    dist[source] = 0.0;
+    heap.push(source, 0.0);
+
+    while let Some((u, d)) = heap.pop() {
+        for (v, w) in graph.neighbors(u).iter().zip(graph.weights(u)) {
+            let nd = d + w;
+            if nd < dist[*v] {
+                dist[*v] = nd;
+                pred[*v] = u;
+                heap.decrease_key(*v, nd);
+            }
+        }
+    }
+
+    return (dist, pred);
+}
+
+// Bellman–Ford with early termination. Weights may be negative.
+// Returns None if a negative cycle is reachable from the source.
+pub fn bellman_ford(graph: &WCSR, source: usize) -> Option<(Vec<f64>, Vec<usize>)>{
+    let n_nodes = graph.n_nodes();
+    let mut dist = vec![f64::INFINITY; n_nodes];
+    let mut pred = vec![NO_PRED; n_nodes];
+
+    dist[source] = 0.0;
+
+    for _ in 0..n_nodes {
+        let mut changed = false;
+        for u in 0..n_nodes {
+            if dist[u] == f64::INFINITY {
+                continue;
+            }
+            for (v, w) in graph.neighbors(u).iter().zip(graph.weights(u)) {
+                if dist[u] + w < dist[*v] {
+                    dist[*v] = dist[u] + w;
+                    pred[*v] = u;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            return Some((dist, pred));
+        }
+    }
+
+    // still relaxing after n rounds
+    return None;
+}
+
+// Delta-stepping (Meyer & Sanders). Nodes are settled in buckets of width delta; the edges
+// of a bucket are relaxed in parallel, light edges (weight <= delta) repeatedly until the
+// bucket is empty and heavy edges once. Weights must be non-negative.
+pub fn delta_stepping(graph: &WCSR, source: usize, delta: f64, n_thread: usize) -> (Vec<f64>, Vec<usize>){
+    assert!(delta > 0.0, "delta must be positive");
+
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let mut dist = vec![f64::INFINITY; n_nodes];
+    let mut pred = vec![NO_PRED; n_nodes];
+
+    let bucket_of = |d: f64| (d / delta) as usize;
+
+    dist[source] = 0.0;
+    let mut buckets : Vec<Vec<usize>> = vec![vec![source]];
+    let mut in_frontier = vec![false; n_nodes];
+
+    let mut i = 0usize;
+    while i < buckets.len() {
+        let mut settled = vec![];
+
+        while !buckets[i].is_empty() {
+            // nodes may have moved to a lower bucket, or be pushed several times
+            let mut frontier = vec![];
+            for u in std::mem::take(&mut buckets[i]) {
+                if !in_frontier[u] && bucket_of(dist[u]) == i {
+                    in_frontier[u] = true;
+                    frontier.push(u);
+                }
+            }
+            for u in &frontier {
+                in_frontier[*u] = false;
+            }
+
+            for v in relax(graph, &frontier, |w| w <= delta, &mut dist, &mut pred, n_thread) {
+                push_bucket(&mut buckets, bucket_of(dist[v]), v);
+            }
+            settled.extend(frontier);
+        }
+        settled.sort_unstable();
+        settled.dedup();
+
+        for v in relax(graph, &settled, |w| w > delta, &mut dist, &mut pred, n_thread) {
+            push_bucket(&mut buckets, bucket_of(dist[v]), v);
+        }
+
+        i += 1;
+    }
+
+    return (dist, pred);
+}
+
+fn push_bucket(buckets: &mut Vec<Vec<usize>>, b: usize, v: usize){
+    if b >= buckets.len() {
+        buckets.resize(b + 1, vec![]);
+    }
+    buckets[b].push(v);
+}
+
+// Relaxes the selected out-edges of the frontier and returns the nodes whose distance dropped.
+// Threads first generate requests, grouped by the range of nodes each thread owns, and then
+// apply the requests on their own range, so no two threads write the same node.
+fn relax<S: Fn(f64) -> bool + Sync>(graph: &WCSR, frontier: &[usize], select: S, dist: &mut [f64], pred: &mut [usize],
+                                      n_thread: usize) -> Vec<usize>{
+    let chunk_size = graph.n_nodes().div_ceil(n_thread).max(1);
+    let select = &select;
+
+    let mut requests : Vec<Vec<Vec<(usize, f64, usize)>>> = vec![];
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for i in 0..n_thread {
+            let dist = &*dist;
+            threads.push(scope.spawn(move |_| -> Vec<Vec<(usize, f64, usize)>> {
+                let mut requests = vec![vec![]; n_thread];
+                for u in frontier.iter().skip(i).step_by(n_thread) {
+                    for (v, w) in graph.neighbors(*u).iter().zip(graph.weights(*u)) {
+                        let nd = dist[*u] + w;
+                        if select(*w) && nd < dist[*v] {
+                            requests[*v / chunk_size].push((*v, nd, *u));
+                        }
+                    }
+                }
+                return requests;
+            }));
+        }
+
+        for t in threads {
+            requests.push(t.join().unwrap());
+        }
+    }).unwrap();
+
+    let mut updated = vec![];
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for (i, (dist, pred)) in dist.chunks_mut(chunk_size).zip(pred.chunks_mut(chunk_size)).enumerate() {
+            let requests = &requests;
+            threads.push(scope.spawn(move |_| -> Vec<usize> {
+                let mut updated = vec![];
+                let offset = i * chunk_size;
+                for (v, nd, u) in requests.iter().flat_map(|r| r[i].iter()) {
+                    let j = *v - offset;
+                    if *nd < dist[j] {
+                        updated.push(*v);
+                        dist[j] = *nd;
+                        pred[j] = *u;
+                    }
+                }
+                return updated;
+            }));
+        }
+
+        for t in threads {
+            updated.extend(t.join().unwrap());
+        }
+    }).unwrap();
+
+    updated.sort_unstable();
+    updated.dedup();
+
+    return updated;
+}
+
+// Dijkstra from every source, with sources distributed over threads.
+// result[s] holds the distances and predecessors from s.
+pub fn all_pairs(graph: &WCSR, n_thread: usize) -> Vec<(Vec<f64>, Vec<usize>)>{
+    let n_nodes = graph.n_nodes();
+    let n_thread = n_thread.max(1);
+    let mut result : Vec<(Vec<f64>, Vec<usize>)> = vec![(vec![], vec![]); n_nodes];
+
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for i in 0..n_thread {
+            threads.push(scope.spawn(move |_| -> Vec<(Vec<f64>, Vec<usize>)> {
+                return (i..n_nodes).step_by(n_thread).map(|s| dijkstra(graph, s)).collect();
+            }));
+        }
+
+        for (i, t) in threads.into_iter().enumerate() {
+            for (j, r) in t.join().unwrap().into_iter().enumerate() {
+                result[i + j * n_thread] = r;
+            }
+        }
+    }).unwrap();
+
+    return result;
+}
+
+// Nodes on the shortest path from the source to target, or None if target is unreachable.
+pub fn path(pred: &[usize], source: usize, target: usize) -> Option<Vec<usize>>{
+    let mut path = vec![target];
+    let mut u = target;
+    while u != source {
+        u = pred[u];
+        if u == NO_PRED {
+            return None;
+        }
+        path.push(u);
+    }
+    path.reverse();
+    return Some(path);
+}
@@ -1,122 +1,464 @@
-use super::union_find::UnionFind;
-use super::heap;
-use std::collections::{HashSet, HashMap};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-pub fn slashburn(edges: &[(usize, usize)], n_nodes: usize, k: usize) -> Vec<usize>{
+use crossbeam;
 
-    let mut remain_edges : Vec<(usize, usize)> = edges.iter().copied().collect();
-    let mut remain_nodes : HashSet<usize> = (0..n_nodes).collect();
-    let mut new_node_order = vec![0usize; n_nodes];
-    let mut cur_hubs = 0;
-    let mut cur_spokes = n_nodes;
+use super::csr::CSR;
+use super::rng::Rng;
 
-    let mut degrees = vec![0; n_nodes].into_boxed_slice();
+// SlashBurn (Kang & Faloutsos, Lim et al.) over an undirected CSR (both directions of each edge
+// are stored, e.g. built by `CSR::from_edges`). Every round removes the k nodes of largest degree
+// (hubs), which go to the front of the order, then the nodes outside the giant connected
+// component (spokes), which go to the back; the next round runs on the giant component.
+// The result holds the order, i.e. order[i] is the node at position i, and the trace of the rounds.
+//
+// Hubs are sorted by decreasing degree, ties being broken by `SlashBurnConfig::tie_break`.
+// Spokes are grouped by component, components by their smallest node, nodes by id. Among
+// components of equal size, the one with the smallest node is the giant one. When a stopping
+// rule ends the rounds early, the giant component fills the middle of the order by increasing id.
 
-    let mut uf = UnionFind::new(n_nodes);
+pub enum HubCount{
+    Absolute(usize),
+    // ceil(f * n_nodes)
+    Fraction(f64)
+}
 
-    for (u, v) in edges{
-        degrees[*u] += 1;
-        degrees[*v] += 1;
+// Order of hubs with equal degrees.
+pub enum TieBreak{
+    SmallestId,
+    LargestId,
+    // a random priority per node, drawn from the seed
+    Random(u64)
+}
+
+pub struct SlashBurnConfig{
+    pub k: HubCount,
+    // stops after this many rounds
+    pub max_iterations: Option<usize>,
+    // stops once the giant component has fewer nodes
+    pub min_gcc_size: usize,
+    pub tie_break: TieBreak
+}
+
+impl Default for SlashBurnConfig{
+    // the setting of the paper, k = 0.5% of the nodes, running until no node is left.
+    fn default() -> Self{
+        Self {
+            k: HubCount::Fraction(0.005),
+            max_iterations: None,
+            min_gcc_size: 0,
+            tie_break: TieBreak::SmallestId
+        }
     }
+}
 
-    loop{
-        eprintln!("remain edges: {}, remain_nodes: {}", remain_edges.len(), remain_nodes.len());
+impl SlashBurnConfig{
 
-        let hubs_remain = topk(k, &remain_nodes, &degrees);
-        let hubset_remain : HashSet<usize> = hubs_remain.iter().copied().collect();
+    // hubs removed per round, at least 1.
+    pub fn hubs_per_round(&self, n_nodes: usize) -> usize{
+        let k = match self.k {
+            HubCount::Absolute(k) => k,
+            HubCount::Fraction(f) => (f * n_nodes as f64).ceil() as usize
+        };
+        return k.max(1);
+    }
 
-        remain_nodes.retain(|u| !hubset_remain.contains(u));
-        
-        remain_edges.retain(|(u, v)| {
-            if remain_nodes.contains(u) && remain_nodes.contains(v) {
-                return true;
-            }
-            else{
-                degrees[*u] -= 1;
-                degrees[*v] -= 1;
-                return false;
+    // priority of each node among nodes of equal degree, smaller first.
+    fn tie_ranks(&self, n_nodes: usize) -> Vec<usize>{
+        match self.tie_break {
+            TieBreak::SmallestId => (0..n_nodes).collect(),
+            TieBreak::LargestId => (0..n_nodes).rev().collect(),
+            TieBreak::Random(seed) => {
+                let mut ranks : Vec<usize> = (0..n_nodes).collect();
+                let mut rng = Rng::new(seed);
+                for i in (1..n_nodes).rev() {
+                    ranks.swap(i, rng.gen_range(i + 1));
+                }
+                ranks
             }
-        });
+        }
+    }
+}
+
+// Spokes of one component, identified by its smallest node.
+pub struct SpokeGroup{
+    pub component: usize,
+    pub nodes: Vec<usize>
+}
+
+pub struct SlashBurnResult{
+    // order[i] is the node at position i
+    pub order: Vec<usize>,
+    // rank[u] is the position of node u
+    pub rank: Vec<usize>,
+    // in order of removal, i.e. order[..hubs.len()]
+    pub hubs: Vec<usize>,
+    // the spokes removed by each round
+    pub spokes: Vec<Vec<SpokeGroup>>,
+    // size of the giant component after each round
+    pub gcc_sizes: Vec<usize>,
+    // hubs over nodes: the width of the wings of the reordered adjacency matrix
+    pub wing_width_ratio: f64
+}
+
+impl SlashBurnResult{
+
+    // Positions where the hubs of each round end and where the spokes of each round start,
+    // sorted, without 0 and n_nodes, e.g. for the grid lines of `spy::SpyConfig`.
+    pub fn boundaries(&self) -> Vec<usize>{
+        let n_nodes = self.order.len();
+        let mut boundaries = vec![];
+        let mut remaining = n_nodes;
+        let mut cur_hubs = 0usize;
+        let mut cur_spokes = n_nodes;
+        for (groups, gcc_size) in self.spokes.iter().zip(&self.gcc_sizes) {
+            let n_spokes : usize = groups.iter().map(|g| g.nodes.len()).sum();
+            cur_hubs += remaining - gcc_size - n_spokes;
+            cur_spokes -= n_spokes;
+            remaining = *gcc_size;
+            boundaries.push(cur_hubs);
+            boundaries.push(cur_spokes);
+        }
+        boundaries.retain(|b| *b > 0 && *b < n_nodes);
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        return boundaries;
+    }
+}
+
+// State after each round, passed to the progress callback.
+pub struct Progress{
+    pub iteration: usize,
+    pub n_hubs: usize,
+    pub n_spokes: usize,
+    pub gcc_nodes: usize,
+    pub gcc_edges: usize
+}
+
+pub fn slashburn(graph: &CSR, config: &SlashBurnConfig) -> SlashBurnResult{
+    return run(graph, config, 1, None);
+}
+
+// Hub selection, degree updates and component search are split over threads; components are
+// found by a concurrent union-find instead of BFS. The result is the same as `slashburn`'s.
+pub fn slashburn_parallel(graph: &CSR, config: &SlashBurnConfig, n_thread: usize) -> SlashBurnResult{
+    return run(graph, config, n_thread.max(1), None);
+}
+
+// Like `slashburn_parallel`, calling progress after every round.
+pub fn slashburn_with_progress<F: FnMut(&Progress)>(graph: &CSR, config: &SlashBurnConfig, n_thread: usize,
+                                                    mut progress: F) -> SlashBurnResult{
+    return run(graph, config, n_thread.max(1), Some(&mut progress));
+}
+
+// progress is optional so that plain runs skip counting the edges of the giant component.
+fn run(graph: &CSR, config: &SlashBurnConfig, n_thread: usize, mut progress: Option<&mut dyn FnMut(&Progress)>) -> SlashBurnResult{
+    let n_nodes = graph.n_nodes();
+    let k = config.hubs_per_round(n_nodes);
+    let ties = config.tie_ranks(n_nodes);
+    let mut new_node_order = vec![0usize; n_nodes];
+    let mut cur_hubs = 0;
+    let mut cur_spokes = n_nodes;
+    let mut spokes = vec![];
+    let mut gcc_sizes = vec![];
+
+    // degrees within the remaining graph
+    let mut degrees : Vec<AtomicUsize> = (0..n_nodes).map(|u| AtomicUsize::new(graph.degree(u))).collect();
+    let mut alive = vec![true; n_nodes];
+    let mut remain_nodes : Vec<usize> = (0..n_nodes).collect();
+    let mut components = Components::new(n_nodes);
+
+    while !remain_nodes.is_empty() {
+        // stopping rules are checked before picking hubs, so that they also hold for the input graph
+        let stop = config.max_iterations.is_some_and(|m| gcc_sizes.len() >= m) || remain_nodes.len() < config.min_gcc_size;
+        if stop {
+            break;
+        }
+
+        let hubs_remain = if n_thread == 1 {
+            topk(k, &remain_nodes, &degrees, &ties)
+        }
+        else {
+            topk_parallel(k, &remain_nodes, &degrees, &ties, n_thread)
+        };
+
+        for h in &hubs_remain {
+            alive[*h] = false;
+        }
+        remove_hubs(graph, &hubs_remain, &alive, &mut degrees, n_thread);
+        remain_nodes.retain(|u| alive[*u]);
 
         new_node_order[cur_hubs..cur_hubs + hubs_remain.len()].clone_from_slice(&hubs_remain);
         cur_hubs += hubs_remain.len();
-        // hubs.extend(hubs_remain.iter());
 
-        if remain_nodes.is_empty() {
-            break;
+        let spokes_remain = if remain_nodes.is_empty() {
+            vec![]
+        }
+        else {
+            if n_thread == 1 {
+                components.bfs(graph, &remain_nodes, &alive);
+            }
+            else {
+                components.union_find(graph, &remain_nodes, &alive, n_thread);
+            }
+            components.remove_spokes(&mut remain_nodes, &mut alive)
+        };
+
+        for group in spokes_remain.iter().rev() {
+            new_node_order[cur_spokes-group.nodes.len()..cur_spokes].clone_from_slice(&group.nodes);
+            cur_spokes -= group.nodes.len();
         }
-        
-        let spokes_remain = find_and_remove_spokes(&remain_edges, &mut remain_nodes, &mut uf);
-        
-        
-        new_node_order[cur_spokes-spokes_remain.len()..cur_spokes].clone_from_slice(&spokes_remain);
-        cur_spokes -= spokes_remain.len();
-        // spokes.extend(spokes_remain.iter().rev());
+        spokes.push(spokes_remain);
+        gcc_sizes.push(remain_nodes.len());
 
-        if remain_nodes.is_empty() {
-            break;
+        if let Some(progress) = &mut progress {
+            progress(&Progress{
+                iteration: gcc_sizes.len(),
+                n_hubs: cur_hubs,
+                n_spokes: n_nodes - cur_spokes,
+                gcc_nodes: remain_nodes.len(),
+                gcc_edges: remain_nodes.iter().map(|u| *degrees[*u].get_mut()).sum::<usize>() / 2
+            });
         }
     }
 
+    let n_hubs = cur_hubs;
+    new_node_order[cur_hubs..cur_hubs + remain_nodes.len()].clone_from_slice(&remain_nodes);
+    cur_hubs += remain_nodes.len();
     assert_eq!(cur_spokes, cur_hubs);
 
-    return new_node_order;
+    let mut rank = vec![0usize; n_nodes];
+    for (i, u) in new_node_order.iter().enumerate() {
+        rank[*u] = i;
+    }
 
+    return SlashBurnResult{
+        hubs: new_node_order[..n_hubs].to_vec(),
+        order: new_node_order,
+        rank,
+        spokes,
+        gcc_sizes,
+        wing_width_ratio: if n_nodes > 0 { n_hubs as f64 / n_nodes as f64 } else { 0.0 }
+    };
 }
 
-fn find_and_remove_spokes(edges: &[(usize, usize)], nodes: &mut HashSet<usize>, uf: &mut UnionFind) -> Vec<usize>{
-    
-    uf.reset(nodes.iter());
+// Decrements the degrees of the remaining neighbors of the hubs, already marked as removed.
+fn remove_hubs(graph: &CSR, hubs: &[usize], alive: &[bool], degrees: &mut [AtomicUsize], n_thread: usize){
+    if n_thread == 1 {
+        for h in hubs {
+            for v in graph.neighbors(*h) {
+                if alive[*v] {
+                    *degrees[*v].get_mut() -= 1;
+                }
+            }
+        }
+        return;
+    }
+
+    let degrees = &*degrees;
+    crossbeam::scope(|scope| {
+        for i in 0..n_thread {
+            scope.spawn(move |_| {
+                for h in hubs.iter().skip(i).step_by(n_thread) {
+                    for v in graph.neighbors(*h) {
+                        if alive[*v] {
+                            degrees[*v].fetch_sub(1, Ordering::Relaxed);
+                        }
+                    }
+                }
+            });
+        }
+    }).unwrap();
+}
+
+// Connected components of the remaining graph, reusing buffers across rounds.
+struct Components{
+    // smallest node of the component of each remaining node; the parents of the union-find
+    ids: Vec<AtomicUsize>,
+    sizes: Vec<usize>,
+    queue: VecDeque<usize>
+}
+
+impl Components{
+
+    fn new(n_nodes: usize) -> Self{
+        Self {
+            ids: (0..n_nodes).map(|_| AtomicUsize::new(usize::MAX)).collect(),
+            sizes: vec![0; n_nodes],
+            queue: VecDeque::new()
+        }
+    }
 
-    for (u, v) in edges.iter() {
-        uf.union(*u, *v);
+    fn id(&self, u: usize) -> usize{
+        return self.ids[u].load(Ordering::Relaxed);
     }
 
-    let cc : Vec<usize> = nodes.iter().map(|u| uf.find(*u)).collect();
-    
-    let mut cc_sizes : HashMap<usize, usize> = HashMap::new();
-    for c in &cc{
-        *cc_sizes.entry(*c).or_insert(0) += 1;
+    // nodes are visited by increasing id, so the first node of a component is its smallest
+    fn bfs(&mut self, graph: &CSR, nodes: &[usize], alive: &[bool]){
+        for u in nodes {
+            *self.ids[*u].get_mut() = usize::MAX;
+        }
+
+        for s in nodes {
+            if *self.ids[*s].get_mut() != usize::MAX {
+                continue;
+            }
+            *self.ids[*s].get_mut() = *s;
+            self.queue.push_back(*s);
+            while let Some(u) = self.queue.pop_front() {
+                for v in graph.neighbors(u) {
+                    if alive[*v] && *self.ids[*v].get_mut() == usize::MAX {
+                        *self.ids[*v].get_mut() = *s;
+                        self.queue.push_back(*v);
+                    }
+                }
+            }
+        }
     }
 
-    let (gccid, gcccnt) = cc_sizes.into_iter().max_by(|(_, x), (_, y)| x.cmp(y)).expect("What the hell?");
+    // Lock-free union-find where roots are always linked under the smaller root, so each
+    // component ends up rooted at its smallest node whatever the order of the unions.
+    fn union_find(&mut self, graph: &CSR, nodes: &[usize], alive: &[bool], n_thread: usize){
+        for u in nodes {
+            *self.ids[*u].get_mut() = *u;
+        }
 
-    let mut spokes = Vec::with_capacity(nodes.len() - gcccnt);
-    
-    
-    nodes.retain(|u| {
-        if gccid == uf.find(*u) {
-            true
+        let chunk_size = nodes.len().div_ceil(n_thread).max(1);
+        let parents = &self.ids;
+        crossbeam::scope(|scope| {
+            for chunk in nodes.chunks(chunk_size) {
+                scope.spawn(move |_| {
+                    for u in chunk {
+                        for v in graph.neighbors(*u) {
+                            if *u < *v && alive[*v] {
+                                union(parents, *u, *v);
+                            }
+                        }
+                    }
+                });
+            }
+        }).unwrap();
+
+        crossbeam::scope(|scope| {
+            for chunk in nodes.chunks(chunk_size) {
+                scope.spawn(move |_| {
+                    for u in chunk {
+                        let root = find(parents, *u);
+                        parents[*u].store(root, Ordering::Relaxed);
+                    }
+                });
+            }
+        }).unwrap();
+    }
+
+    // Keeps the giant component in `nodes`, sorted by id, and returns the other nodes by component.
+    fn remove_spokes(&mut self, nodes: &mut Vec<usize>, alive: &mut [bool]) -> Vec<SpokeGroup>{
+        for u in nodes.iter() {
+            let c = self.id(*u);
+            self.sizes[c] = 0;
         }
-        else{
-            spokes.push(*u);
-            false
+        for u in nodes.iter() {
+            let c = self.id(*u);
+            self.sizes[c] += 1;
         }
-    });
 
-    spokes.sort_by(|a,b| uf.find(*a).cmp(&uf.find(*b)));
-    
-    return spokes;
-}
+        let (gcc_id, gcc_size) = nodes.iter().filter(|u| self.id(**u) == **u)
+            .map(|u| (*u, self.sizes[*u]))
+            .max_by(|(x, x_size), (y, y_size)| x_size.cmp(y_size).then(y.cmp(x)))
+            .expect("no remaining node");
+
+        let mut spokes = Vec::with_capacity(nodes.len() - gcc_size);
+        nodes.retain(|u| {
+            if self.id(*u) == gcc_id {
+                true
+            }
+            else{
+                spokes.push(*u);
+                alive[*u] = false;
+                false
+            }
+        });
 
-fn topk(k: usize, keys: &HashSet<usize>, values: &[usize]) -> Vec<usize> {
-    
-    let k = if k < keys.len() { k } else { keys.len() }; 
+        spokes.sort_by_key(|u| self.id(*u));
+
+        let mut groups : Vec<SpokeGroup> = vec![];
+        for u in spokes {
+            let c = self.id(u);
+            match groups.last_mut() {
+                Some(group) if group.component == c => group.nodes.push(u),
+                _ => groups.push(SpokeGroup{ component: c, nodes: vec![u] })
+            }
+        }
 
-    let mut key_iter = keys.iter();
+        return groups;
+    }
+}
+
+fn find(parents: &[AtomicUsize], mut u: usize) -> usize{
+    loop {
+        let p = parents[u].load(Ordering::Relaxed);
+        if p == u {
+            return u;
+        }
+        // path halving; parents only ever decrease, so a failed update is harmless
+        let gp = parents[p].load(Ordering::Relaxed);
+        if gp != p {
+            let _ = parents[u].compare_exchange_weak(p, gp, Ordering::Relaxed, Ordering::Relaxed);
+        }
+        u = gp;
+    }
+}
 
-    let mut heap : Vec<usize> = vec![0;k];
-    for i in heap.iter_mut(){
-        *i = *key_iter.next().unwrap();
+fn union(parents: &[AtomicUsize], u: usize, v: usize){
+    loop {
+        let ru = find(parents, u);
+        let rv = find(parents, v);
+        if ru == rv {
+            return;
+        }
+        let (high, low) = if ru > rv { (ru, rv) } else { (rv, ru) };
+        if parents[high].compare_exchange(high, low, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+            return;
+        }
     }
+}
 
-    heap::heapify_indices(&mut heap, &values);
+fn degree_key<'a>(degrees: &'a [AtomicUsize], ties: &'a [usize]) -> impl Fn(&usize) -> (std::cmp::Reverse<usize>, usize) + 'a{
+    return move |u: &usize| (std::cmp::Reverse(degrees[*u].load(Ordering::Relaxed)), ties[*u]);
+}
+
+// the k nodes of largest degree, by decreasing degree then increasing tie rank.
+fn topk(k: usize, nodes: &[usize], degrees: &[AtomicUsize], ties: &[usize]) -> Vec<usize> {
+    let k = k.min(nodes.len());
+    let key = degree_key(degrees, ties);
 
-    for x in key_iter{
-        heap::heap_push_indices(*x, &mut heap, &values);
+    let mut candidates = nodes.to_vec();
+    if k < candidates.len() {
+        candidates.select_nth_unstable_by_key(k, &key);
+        candidates.truncate(k);
     }
+    candidates.sort_unstable_by_key(&key);
 
-    heap::sort_heap_indices(&mut heap, &values);
+    return candidates;
+}
 
-    return heap;
-}
\ No newline at end of file
+// the top k of the union of the top k of each chunk.
+fn topk_parallel(k: usize, nodes: &[usize], degrees: &[AtomicUsize], ties: &[usize], n_thread: usize) -> Vec<usize> {
+    let chunk_size = nodes.len().div_ceil(n_thread).max(1);
+
+    let mut candidates = vec![];
+    crossbeam::scope(|scope| {
+        let mut threads = vec![];
+        for chunk in nodes.chunks(chunk_size) {
+            threads.push(scope.spawn(move |_| -> Vec<usize> {
+                return topk(k, chunk, degrees, ties);
+            }));
+        }
+
+        for t in threads {
+            candidates.extend(t.join().unwrap());
+        }
+    }).unwrap();
+
+    return topk(k, &candidates, degrees, ties);
+}
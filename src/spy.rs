@@ -0,0 +1,289 @@
+use std::fs::File;
+use std::io::{Result, BufWriter, Write};
+
+use crate::vis::Window;
+
+// Spy plots of adjacency matrices: the cells counted by `vis::scaling` or `vis::scaling_window`
+// drawn as an image, row u and column v holding the edges (u, v). Empty cells are white,
+// non-empty ones range from light gray to black with their count, and grid lines are red.
+// Images are written as binary PPM, as PNG without compression, or as SVG.
+
+pub enum Scale{
+    // (count - 1) / (max count - 1)
+    Linear,
+    // ln(count) / ln(max count), which keeps sparse cells visible next to dense ones
+    Log
+}
+
+pub struct SpyConfig{
+    // cells per side, as passed to `vis::scaling`; windows set their own
+    pub n_pixels: usize,
+    // side of a cell in the image
+    pub cell_size: usize,
+    pub scale: Scale,
+    // node positions before which a line is drawn across the matrix, e.g.
+    // `SlashBurnResult::boundaries` for the hub and spoke blocks
+    pub grid: Vec<usize>
+}
+
+impl Default for SpyConfig{
+    fn default() -> Self{
+        Self {
+            n_pixels: 512,
+            cell_size: 1,
+            scale: Scale::Log,
+            grid: vec![]
+        }
+    }
+}
+
+const EMPTY: [u8; 3] = [255, 255, 255];
+const GRID: [u8; 3] = [255, 0, 0];
+// gray of the cells of count 1
+const LIGHTEST: f64 = 224.0;
+
+// An RGB image, row by row.
+pub struct Image{
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>
+}
+
+impl Image{
+
+    pub fn new(width: usize, height: usize) -> Self{
+        Self {
+            width,
+            height,
+            pixels: EMPTY.repeat(width * height)
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> [u8; 3]{
+        let i = 3 * (y * self.width + x);
+        return [self.pixels[i], self.pixels[i+1], self.pixels[i+2]];
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: [u8; 3]){
+        let i = 3 * (y * self.width + x);
+        self.pixels[i..i+3].copy_from_slice(&color);
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 3]){
+        for yy in y..(y + height).min(self.height) {
+            for xx in x..(x + width).min(self.width) {
+                self.set(xx, yy, color);
+            }
+        }
+    }
+
+    // binary PPM (P6).
+    pub fn to_ppm(&self) -> Vec<u8>{
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend_from_slice(&self.pixels);
+        return bytes;
+    }
+
+    // 8-bit RGB PNG, the zlib stream made of stored deflate blocks.
+    pub fn to_png(&self) -> Vec<u8>{
+        // each row starts with filter type 0
+        let mut raw = Vec::with_capacity((3 * self.width + 1) * self.height);
+        for row in self.pixels.chunks(3 * self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut header = vec![];
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth, color type RGB, compression, filter and interlace methods
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut bytes = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        return bytes;
+    }
+
+    pub fn write_ppm(&self, filepath: &str) -> Result<()>{
+        return write_bytes(&self.to_ppm(), filepath);
+    }
+
+    pub fn write_png(&self, filepath: &str) -> Result<()>{
+        return write_bytes(&self.to_png(), filepath);
+    }
+}
+
+fn write_bytes(bytes: &[u8], filepath: &str) -> Result<()>{
+    let mut bw = BufWriter::new(File::create(filepath)?);
+    bw.write_all(bytes)?;
+    bw.flush()?;
+    return Ok(());
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]){
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend_from_slice(kind);
+    bytes.extend_from_slice(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend_from_slice(&crc.to_be_bytes());
+}
+
+// zlib stream of uncompressed deflate blocks, of at most 65535 bytes each.
+fn zlib_stored(data: &[u8]) -> Vec<u8>{
+    const MAX_BLOCK: usize = 65535;
+
+    let mut bytes = vec![0x78, 0x01];
+    let n_blocks = data.len().div_ceil(MAX_BLOCK).max(1);
+    for i in 0..n_blocks {
+        let block = &data[i * MAX_BLOCK..((i + 1) * MAX_BLOCK).min(data.len())];
+        let len = block.len() as u16;
+        bytes.push(if i + 1 == n_blocks { 1 } else { 0 });
+        bytes.extend_from_slice(&len.to_le_bytes());
+        bytes.extend_from_slice(&(!len).to_le_bytes());
+        bytes.extend_from_slice(block);
+    }
+    bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    return bytes;
+}
+
+pub fn crc32(data: &[u8]) -> u32{
+    let mut table = [0u32; 256];
+    for i in 0..256 {
+        let mut c = i as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+        }
+        table[i] = c;
+    }
+
+    let mut crc = !0u32;
+    for b in data {
+        crc = table[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    return !crc;
+}
+
+pub fn adler32(data: &[u8]) -> u32{
+    const MOD: u32 = 65521;
+    // sums stay below 2^32 over this many bytes
+    const CHUNK: usize = 5552;
+
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for chunk in data.chunks(CHUNK) {
+        for x in chunk {
+            a += *x as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    return (b << 16) | a;
+}
+
+// Gray level of each count.
+fn grays(cells: &[(usize, usize, usize)], scale: &Scale) -> Vec<u8>{
+    let max = cells.iter().map(|c| c.2).max().unwrap_or(0);
+    return cells.iter().map(|(_, _, count)| {
+        let t = match scale {
+            Scale::Linear => (*count as f64 - 1.0) / (max as f64 - 1.0).max(1.0),
+            Scale::Log => (*count as f64).ln() / (max as f64).ln().max(f64::MIN_POSITIVE)
+        };
+        (LIGHTEST * (1.0 - t.clamp(0.0, 1.0))).round() as u8
+    }).collect();
+}
+
+// Image coordinates of the grid lines crossing the window, horizontal then vertical.
+fn grid_lines(window: &Window, config: &SpyConfig) -> (Vec<usize>, Vec<usize>){
+    let lines = |cell: &dyn Fn(usize) -> Option<usize>, first: usize| {
+        let mut lines : Vec<usize> = config.grid.iter().filter(|b| **b > first)
+            .filter_map(|b| cell(*b)).map(|c| c * config.cell_size).collect();
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    };
+    return (lines(&|u| window.row_cell(u), window.rows.start), lines(&|v| window.col_cell(v), window.cols.start));
+}
+
+// cells of `vis::scaling`, identified by their first node, as cells of the square window.
+fn square_cells(cells: &[(usize, usize, usize)], window: &Window) -> Vec<(usize, usize, usize)>{
+    return cells.iter().map(|(u, v, count)| {
+        let (y, x) = window.cell(*u, *v).unwrap();
+        (y, x, *count)
+    }).collect();
+}
+
+// Draws the cells of `vis::scaling(edges, n_nodes, config.n_pixels)`.
+pub fn render(cells: &[(usize, usize, usize)], n_nodes: usize, config: &SpyConfig) -> Image{
+    if n_nodes == 0 {
+        let side = config.n_pixels * config.cell_size;
+        return Image::new(side, side);
+    }
+    let window = Window::square(n_nodes, config.n_pixels);
+    return render_window(&square_cells(cells, &window), &window, config);
+}
+
+// Draws the cells of `vis::scaling_window(edges, window)`. The window sets the number of cells,
+// and grid lines are drawn where they cross its rows or columns.
+pub fn render_window(cells: &[(usize, usize, usize)], window: &Window, config: &SpyConfig) -> Image{
+    let width = window.col_pixels * config.cell_size;
+    let height = window.row_pixels * config.cell_size;
+    let mut image = Image::new(width, height);
+
+    let grays = grays(cells, &config.scale);
+    for (i, (y, x, _)) in cells.iter().enumerate() {
+        image.fill(x * config.cell_size, y * config.cell_size, config.cell_size, config.cell_size, [grays[i]; 3]);
+    }
+
+    let (rows, cols) = grid_lines(window, config);
+    for y in rows {
+        image.fill(0, y, width, 1, GRID);
+    }
+    for x in cols {
+        image.fill(x, 0, 1, height, GRID);
+    }
+    return image;
+}
+
+// The same plot as `render`, as an SVG document with one rectangle per non-empty cell.
+pub fn to_svg(cells: &[(usize, usize, usize)], n_nodes: usize, config: &SpyConfig) -> String{
+    if n_nodes == 0 {
+        return window_to_svg(&[], &Window::new(0..0, 0..0, config.n_pixels, config.n_pixels), config);
+    }
+    let window = Window::square(n_nodes, config.n_pixels);
+    return window_to_svg(&square_cells(cells, &window), &window, config);
+}
+
+// The same plot as `render_window`, as an SVG document.
+pub fn window_to_svg(cells: &[(usize, usize, usize)], window: &Window, config: &SpyConfig) -> String{
+    let width = window.col_pixels * config.cell_size;
+    let height = window.row_pixels * config.cell_size;
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" shape-rendering=\"crispEdges\">\n", width, height);
+    svg += &format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", width, height);
+
+    // sorted, for the same document whatever the order of the cells
+    let grays = grays(cells, &config.scale);
+    let mut rects : Vec<(usize, usize, u8)> = cells.iter().zip(&grays).map(|((y, x, _), g)| (*y, *x, *g)).collect();
+    rects.sort_unstable();
+    for (y, x, g) in rects {
+        svg += &format!("<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"rgb({3},{3},{3})\"/>\n",
+                        x * config.cell_size, y * config.cell_size, config.cell_size, g);
+    }
+
+    let (rows, cols) = grid_lines(window, config);
+    for y in rows {
+        svg += &format!("<line x1=\"0\" y1=\"{0}.5\" x2=\"{1}\" y2=\"{0}.5\" stroke=\"red\"/>\n", y, width);
+    }
+    for x in cols {
+        svg += &format!("<line x1=\"{0}.5\" y1=\"0\" x2=\"{0}.5\" y2=\"{1}\" stroke=\"red\"/>\n", x, height);
+    }
+    svg += "</svg>\n";
+    return svg;
+}
+
+pub fn write_svg(cells: &[(usize, usize, usize)], n_nodes: usize, config: &SpyConfig, filepath: &str) -> Result<()>{
+    return write_bytes(to_svg(cells, n_nodes, config).as_bytes(), filepath);
+}
@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+
+use crate::csr::CSR;
+use crate::kcore;
+use crate::tricnt;
+use crate::union_find::UnionFind;
+
+// Summary of a dataset stored as an undirected CSR (both directions of each edge, e.g. built by
+// `CSR::from_edges`). Self loops and duplicate edges are counted, then dropped before
+// computing the other statistics on the simple graph. Degrees are out-degrees, and triangles
+// are counted on the undirected pairs, which only matters when the graph is not symmetric.
+pub struct Report{
+    pub n_nodes: usize,
+    // entries of graph.edges
+    pub n_entries: usize,
+    // distinct pairs {u, v} with u != v
+    pub n_edges: usize,
+    // nodes u with a self loop (u, u), however many times it is stored
+    pub self_loops: usize,
+    // extra copies of the pairs (u, v) with u < v, so an edge given twice to `CSR::from_edges`
+    // counts once, like in n_edges
+    pub duplicate_edges: usize,
+    // whether every edge (u, v) comes with (v, u)
+    pub symmetric: bool,
+    pub isolated_nodes: usize,
+    pub min_degree: usize,
+    pub max_degree: usize,
+    pub mean_degree: f64,
+    pub degree_variance: f64,
+    pub degree_skewness: f64,
+    // degree_histogram[d] is the number of nodes of degree d
+    pub degree_histogram: Vec<usize>,
+    pub n_components: usize,
+    // in decreasing order
+    pub component_sizes: Vec<usize>,
+    pub triangles: usize,
+    // 3 * triangles / wedges, i.e. the global clustering coefficient
+    pub transitivity: f64,
+    pub degeneracy: usize,
+    // lower bound given by double sweeps
+    pub estimated_diameter: usize
+}
+
+pub fn report(graph: &CSR) -> Report{
+    let n_nodes = graph.n_nodes();
+
+    let mut self_loops = 0usize;
+    let mut duplicate_edges = 0usize;
+    let mut simple = CSR{
+        nodes: vec![0; n_nodes + 1],
+        edges: Vec::with_capacity(graph.edges.len())
+    };
+    let mut list = vec![];
+    for u in 0..n_nodes {
+        list.clear();
+        list.extend_from_slice(graph.neighbors(u));
+        list.sort_unstable();
+        for (i, v) in list.iter().enumerate() {
+            let repeated = i > 0 && list[i-1] == *v;
+            if *v == u {
+                if !repeated {
+                    self_loops += 1;
+                }
+            }
+            else if repeated {
+                if u < *v {
+                    duplicate_edges += 1;
+                }
+            }
+            else {
+                simple.edges.push(*v);
+            }
+        }
+        simple.nodes[u+1] = simple.edges.len();
+    }
+
+    let transposed = simple.transpose();
+    let symmetric = simple.nodes == transposed.nodes && simple.edges == transposed.edges;
+    let degrees : Vec<usize> = (0..n_nodes).map(|u| simple.degree(u)).collect();
+    let max_degree = degrees.iter().copied().max().unwrap_or(0);
+    let mut degree_histogram = vec![0usize; if n_nodes > 0 { max_degree + 1 } else { 0 }];
+    for d in &degrees {
+        degree_histogram[*d] += 1;
+    }
+
+    let (mean, variance, skewness) = moments(&degrees);
+    let component_sizes = component_sizes(&simple);
+
+    // triangles of the graph oriented from lower to higher ids
+    let pairs = distinct_pairs(&simple);
+    let triangles = tricnt::csr::count(&CSR::from_sorted_edges(&pairs, n_nodes));
+    let wedges : usize = degrees.iter().map(|d| d * d.saturating_sub(1) / 2).sum();
+
+    return Report{
+        n_nodes,
+        n_entries: graph.edges.len(),
+        n_edges: pairs.len(),
+        self_loops,
+        duplicate_edges,
+        symmetric,
+        isolated_nodes: degree_histogram.first().copied().unwrap_or(0),
+        min_degree: degrees.iter().copied().min().unwrap_or(0),
+        max_degree,
+        mean_degree: mean,
+        degree_variance: variance,
+        degree_skewness: skewness,
+        degree_histogram,
+        n_components: component_sizes.len(),
+        component_sizes,
+        triangles,
+        transitivity: if wedges > 0 { 3.0 * triangles as f64 / wedges as f64 } else { 0.0 },
+        degeneracy: kcore::degeneracy(&kcore::core_numbers(&simple)),
+        estimated_diameter: estimate_diameter(&simple)
+    };
+}
+
+impl Report{
+
+    // The report as a JSON object, keys in the order of the fields.
+    pub fn to_json(&self) -> String{
+        let ints = |values: &[usize]| {
+            let values : Vec<String> = values.iter().map(|x| x.to_string()).collect();
+            format!("[{}]", values.join(", "))
+        };
+
+        let fields = [
+            ("n_nodes", self.n_nodes.to_string()),
+            ("n_entries", self.n_entries.to_string()),
+            ("n_edges", self.n_edges.to_string()),
+            ("self_loops", self.self_loops.to_string()),
+            ("duplicate_edges", self.duplicate_edges.to_string()),
+            ("symmetric", self.symmetric.to_string()),
+            ("isolated_nodes", self.isolated_nodes.to_string()),
+            ("min_degree", self.min_degree.to_string()),
+            ("max_degree", self.max_degree.to_string()),
+            ("mean_degree", json_f64(self.mean_degree)),
+            ("degree_variance", json_f64(self.degree_variance)),
+            ("degree_skewness", json_f64(self.degree_skewness)),
+            ("degree_histogram", ints(&self.degree_histogram)),
+            ("n_components", self.n_components.to_string()),
+            ("component_sizes", ints(&self.component_sizes)),
+            ("triangles", self.triangles.to_string()),
+            ("transitivity", json_f64(self.transitivity)),
+            ("degeneracy", self.degeneracy.to_string()),
+            ("estimated_diameter", self.estimated_diameter.to_string())
+        ];
+
+        let fields : Vec<String> = fields.iter().map(|(k, v)| format!("  \"{}\": {}", k, v)).collect();
+        return format!("{{\n{}\n}}", fields.join(",\n"));
+    }
+}
+
+// JSON has no NaN nor infinities.
+fn json_f64(x: f64) -> String{
+    if x.is_finite() {
+        return format!("{:?}", x);
+    }
+    return "null".to_string();
+}
+
+// mean, variance and skewness.
+fn moments(values: &[usize]) -> (f64, f64, f64){
+    if values.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let n = values.len() as f64;
+    let mean = values.iter().map(|x| *x as f64).sum::<f64>() / n;
+    let mut m2 = 0f64;
+    let mut m3 = 0f64;
+    for x in values {
+        let d = *x as f64 - mean;
+        m2 += d * d;
+        m3 += d * d * d;
+    }
+    m2 /= n;
+    m3 /= n;
+
+    let skewness = if m2 > 0.0 { m3 / m2.powf(1.5) } else { 0.0 };
+    return (mean, m2, skewness);
+}
+
+// sorted pairs (u, v), u < v, with an edge in either direction.
+fn distinct_pairs(graph: &CSR) -> Vec<(usize, usize)>{
+    let mut pairs : Vec<(usize, usize)> = graph.iter_edges().map(|(u, v)| (u.min(v), u.max(v))).collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    return pairs;
+}
+
+// sizes of the weakly connected components, in decreasing order.
+fn component_sizes(graph: &CSR) -> Vec<usize>{
+    let n_nodes = graph.n_nodes();
+    let mut uf = UnionFind::new(n_nodes);
+    for (u, v) in graph.iter_edges() {
+        uf.union(u, v);
+    }
+
+    let mut sizes = vec![0usize; n_nodes];
+    for u in 0..n_nodes {
+        sizes[uf.find(u)] += 1;
+    }
+    sizes.retain(|s| *s > 0);
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    return sizes;
+}
+
+// Double sweep (Magnien, Latapy & Habib): the eccentricity of the farthest node from a BFS is a
+// lower bound of the diameter, usually tight. Sweeps start from a node of largest degree, which
+// is most likely in the largest component, and continue from the farthest node while the bound
+// improves.
+fn estimate_diameter(graph: &CSR) -> usize{
+    const MAX_SWEEPS: usize = 8;
+
+    let n_nodes = graph.n_nodes();
+    if n_nodes == 0 {
+        return 0;
+    }
+
+    let mut dist = vec![usize::MAX; n_nodes];
+    let mut queue = VecDeque::new();
+    let mut bfs = |s: usize| -> (usize, usize) {
+        dist.iter_mut().for_each(|d| *d = usize::MAX);
+        dist[s] = 0;
+        queue.push_back(s);
+        let mut farthest = s;
+        while let Some(u) = queue.pop_front() {
+            farthest = u;
+            for v in graph.neighbors(u) {
+                if dist[*v] == usize::MAX {
+                    dist[*v] = dist[u] + 1;
+                    queue.push_back(*v);
+                }
+            }
+        }
+        (farthest, dist[farthest])
+    };
+
+    let mut source = (0..n_nodes).max_by_key(|u| graph.degree(*u)).unwrap();
+    let mut diameter = 0usize;
+    for _ in 0..MAX_SWEEPS {
+        let (farthest, ecc) = bfs(source);
+        if ecc <= diameter && diameter > 0 {
+            break;
+        }
+        diameter = diameter.max(ecc);
+        source = farthest;
+    }
+    return diameter;
+}
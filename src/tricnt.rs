@@ -59,6 +59,30 @@ pub mod csr {
             }
         }
     }
+
+    // calls f(pu, pv) for each common neighbor of u and v,
+    // where pu and pv are its positions in graph.edges from u's and v's neighbor lists.
+    pub fn for_each_intersect<F: FnMut(usize, usize)>(u: usize, v: usize, graph: &CSR, mut f: F) {
+        let (mut pu, u_end) = (graph.nodes[u], graph.nodes[u+1]);
+        let (mut pv, v_end) = (graph.nodes[v], graph.nodes[v+1]);
+
+        while pu < u_end && pv < v_end {
+            let un = graph.edges[pu];
+            let vn = graph.edges[pv];
+
+            if un < vn {
+                pu += 1;
+            }
+            else if un > vn {
+                pv += 1;
+            }
+            else {
+                f(pu, pv);
+                pu += 1;
+                pv += 1;
+            }
+        }
+    }
 }
 
 pub mod csbv {
@@ -78,7 +102,7 @@ pub mod csbv {
                     let mut cnt = 0usize;
                     for u in (i..graph.n_nodes()).step_by(n_thread) {
                         for v in graph.neighbor_iter(u) {
-                            cnt += count_intersect(u, v, &graph);
+                            cnt += count_intersect(u, v, graph);
                         }
                     }
                     return cnt;
@@ -100,7 +124,7 @@ pub mod csbv {
     
         for u in 0..graph.n_nodes() {
             for v in graph.neighbor_iter(u) {
-                cnt += count_intersect(u, v, &graph);
+                cnt += count_intersect(u, v, graph);
             }
         }
         return cnt;
@@ -164,7 +188,7 @@ pub fn count_total(adj: Vec<Vec<usize>>) -> usize{
     return cnt;
 }
 
-fn count_intersect_btw_sorted(adj_u: &Vec<usize>, adj_v: &Vec<usize>) -> usize{
+fn count_intersect_btw_sorted(adj_u: &[usize], adj_v: &[usize]) -> usize{
     let mut iter_u = adj_u.iter();
     let mut iter_v = adj_v.iter();
     let mut un = match iter_u.next() { Some(un) => un, None => return 0 };
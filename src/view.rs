@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+
+use crate::csr::CSR;
+
+// Views restricting a CSR to a subset of its nodes, and compact copies of such subgraphs.
+// Materialized subgraphs are relabeled to 0..k and returned with the original id of each new
+// node. New ids follow the order of the original ids, so sorted neighbor lists stay sorted.
+
+// The graph restricted to the nodes of a mask, without copying it. Node ids are unchanged.
+pub struct FilteredCSR<'a>{
+    graph: &'a CSR,
+    mask: Vec<bool>
+}
+
+impl<'a> FilteredCSR<'a>{
+
+    pub fn new(graph: &'a CSR, mask: Vec<bool>) -> Self{
+        assert_eq!(mask.len(), graph.n_nodes(), "the mask must have one entry per node");
+        Self { graph, mask }
+    }
+
+    pub fn from_nodes(graph: &'a CSR, nodes: &[usize]) -> Self{
+        let mut mask = vec![false; graph.n_nodes()];
+        for u in nodes {
+            mask[*u] = true;
+        }
+        Self { graph, mask }
+    }
+
+    pub fn contains(&self, u: usize) -> bool{
+        return self.mask[u];
+    }
+
+    // hides u and its edges.
+    pub fn remove(&mut self, u: usize){
+        self.mask[u] = false;
+    }
+
+    pub fn insert(&mut self, u: usize){
+        self.mask[u] = true;
+    }
+
+    // neighbors of u within the view; u itself is expected to be in the view.
+    pub fn neighbors(&self, u: usize) -> impl Iterator<Item = usize> + '_{
+        return self.graph.neighbors(u).iter().copied().filter(|v| self.mask[*v]);
+    }
+
+    pub fn degree(&self, u: usize) -> usize{
+        return self.neighbors(u).count();
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = usize> + '_{
+        return (0..self.mask.len()).filter(|u| self.mask[*u]);
+    }
+
+    pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_{
+        return self.nodes().flat_map(move |u| self.neighbors(u).map(move |v| (u, v)));
+    }
+
+    pub fn n_nodes(&self) -> usize{
+        return self.mask.iter().filter(|x| **x).count();
+    }
+
+    pub fn to_csr(&self) -> (CSR, Vec<usize>){
+        return induced_subgraph(self.graph, &self.nodes().collect::<Vec<usize>>());
+    }
+}
+
+// The subgraph induced by the given nodes, relabeled.
+pub fn induced_subgraph(graph: &CSR, nodes: &[usize]) -> (CSR, Vec<usize>){
+    let mut nodes = nodes.to_vec();
+    nodes.sort_unstable();
+    nodes.dedup();
+
+    let mut new_ids = vec![usize::MAX; graph.n_nodes()];
+    for (i, u) in nodes.iter().enumerate() {
+        new_ids[*u] = i;
+    }
+
+    let mut sub = CSR{
+        nodes: vec![0; nodes.len() + 1],
+        edges: vec![]
+    };
+    for (i, u) in nodes.iter().enumerate() {
+        for v in graph.neighbors(*u) {
+            if new_ids[*v] != usize::MAX {
+                sub.edges.push(new_ids[*v]);
+            }
+        }
+        sub.nodes[i+1] = sub.edges.len();
+    }
+
+    return (sub, nodes);
+}
+
+// Nodes reachable from center within k hops along out-edges, in BFS order.
+pub fn k_hop(graph: &CSR, center: usize, k: usize) -> Vec<usize>{
+    let mut dist = vec![usize::MAX; graph.n_nodes()];
+    let mut order = vec![];
+    let mut queue = VecDeque::new();
+
+    dist[center] = 0;
+    queue.push_back(center);
+    while let Some(u) = queue.pop_front() {
+        order.push(u);
+        if dist[u] == k {
+            continue;
+        }
+        for v in graph.neighbors(u) {
+            if dist[*v] == usize::MAX {
+                dist[*v] = dist[u] + 1;
+                queue.push_back(*v);
+            }
+        }
+    }
+
+    return order;
+}
+
+// The k-hop ego network of center: the subgraph induced by the nodes within k hops, relabeled.
+pub fn ego_network(graph: &CSR, center: usize, k: usize) -> (CSR, Vec<usize>){
+    return induced_subgraph(graph, &k_hop(graph, center, k));
+}
@@ -1,4 +1,13 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result};
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossbeam;
+
+use crate::csbv::CSBV;
+use crate::csr::CSR;
 
 fn gcd(mut a: usize,mut b: usize) -> usize{
     if a < b {
@@ -13,20 +22,9 @@ fn gcd(mut a: usize,mut b: usize) -> usize{
 
 }
 
-fn mul_mod(mut x: usize, mut y: usize, m: usize) -> usize {
-
-    let mut res = 0usize;
-    x = x % m;
-
-    while y > 0 {
-        if y % 2 == 1 {
-            res = (res + x) % m;
-        }
-        x = (x << 1) % m;
-        y = y >> 1;
-    }
-
-    return res % m;
+// x * y % m, exact for any usize operands.
+fn mul_mod(x: usize, y: usize, m: usize) -> usize{
+    return (x as u128 * y as u128 % m as u128) as usize;
 }
 
 pub fn scaling(edges: &[(usize, usize)], n_nodes: usize, n_pixels: usize) -> Vec<(usize, usize, usize)>{
@@ -47,4 +45,240 @@ pub fn scaling(edges: &[(usize, usize)], n_nodes: usize, n_pixels: usize) -> Vec
     let new_edges: Vec<(usize, usize, usize)> = edges_cnt.iter().map(|((u, v), c)| (*u, *v, *c)).collect();
     
     return new_edges;
-}
\ No newline at end of file
+}
+
+// A window of a matrix drawn on row_pixels x col_pixels cells: the rows and columns of the
+// ranges are node ids, of a single graph for adjacency matrices, directed or not, or of the two
+// sides of a bipartite graph (`Bipartite::left` edges being (left, right) pairs). Zooming in on
+// a block of a reordered matrix is a window over its ranges.
+pub struct Window{
+    pub rows: Range<usize>,
+    pub cols: Range<usize>,
+    pub row_pixels: usize,
+    pub col_pixels: usize
+}
+
+impl Window{
+
+    pub fn new(rows: Range<usize>, cols: Range<usize>, row_pixels: usize, col_pixels: usize) -> Self{
+        assert!(row_pixels > 0 && col_pixels > 0, "a window needs at least one cell per side");
+        Self { rows, cols, row_pixels, col_pixels }
+    }
+
+    // The whole n_nodes x n_nodes matrix, cut as by `scaling`.
+    pub fn square(n_nodes: usize, n_pixels: usize) -> Self{
+        return Self::new(0..n_nodes, 0..n_nodes, n_pixels, n_pixels);
+    }
+
+    // cell row of row u, if within the window.
+    pub fn row_cell(&self, u: usize) -> Option<usize>{
+        return range_cell(&self.rows, self.row_pixels, u);
+    }
+
+    pub fn col_cell(&self, v: usize) -> Option<usize>{
+        return range_cell(&self.cols, self.col_pixels, v);
+    }
+
+    pub fn cell(&self, u: usize, v: usize) -> Option<(usize, usize)>{
+        return Some((self.row_cell(u)?, self.col_cell(v)?));
+    }
+}
+
+// Cell i of a range holds the ids first + [i * len / pixels, (i + 1) * len / pixels), rounded up.
+fn range_cell(range: &Range<usize>, n_pixels: usize, u: usize) -> Option<usize>{
+    if !range.contains(&u) {
+        return None;
+    }
+    return Some(((u - range.start) as u128 * n_pixels as u128 / range.len() as u128) as usize);
+}
+
+// Counts the edges (u, v) of each cell (row cell, column cell) of the window, skipping edges
+// outside of it. Cells are sorted.
+pub fn scaling_window(edges: &[(usize, usize)], window: &Window) -> Vec<(usize, usize, usize)>{
+    let mut cells : Vec<(usize, usize)> = edges.iter().filter_map(|(u, v)| window.cell(*u, *v)).collect();
+    cells.sort_unstable();
+
+    let mut counts : Vec<(usize, usize, usize)> = vec![];
+    for (y, x) in cells {
+        match counts.last_mut() {
+            Some(last) if (last.0, last.1) == (y, x) => last.2 += 1,
+            _ => counts.push((y, x, 1))
+        }
+    }
+    return counts;
+}
+
+// Dense counterparts of `scaling_window` for graphs too large for an edge slice: edges are
+// streamed from their source and counted in a dense array of row_pixels * col_pixels cells,
+// row by row, which `dense_cells` turns into the sparse cells. The parallel versions count with
+// atomic adds, so the result does not depend on the number of threads.
+
+// nodes handed to a thread at a time
+const NODE_CHUNK: usize = 1024;
+
+fn atomic_counts(window: &Window) -> Vec<AtomicUsize>{
+    return (0..window.row_pixels * window.col_pixels).map(|_| AtomicUsize::new(0)).collect();
+}
+
+fn into_counts(counts: Vec<AtomicUsize>) -> Vec<usize>{
+    return counts.into_iter().map(|x| x.into_inner()).collect();
+}
+
+// Non-empty cells (row cell, column cell, count) of a dense array, sorted.
+pub fn dense_cells(counts: &[usize], window: &Window) -> Vec<(usize, usize, usize)>{
+    return counts.iter().enumerate().filter(|(_, c)| **c > 0)
+        .map(|(i, c)| (i / window.col_pixels, i % window.col_pixels, *c)).collect();
+}
+
+// Counts edges of any source, e.g. `CSR::iter_edges` or `FilteredCSR::iter_edges`.
+pub fn scaling_stream<I: IntoIterator<Item = (usize, usize)>>(edges: I, window: &Window) -> Vec<usize>{
+    let mut counts = vec![0usize; window.row_pixels * window.col_pixels];
+    for (u, v) in edges {
+        if let Some((y, x)) = window.cell(u, v) {
+            counts[y * window.col_pixels + x] += 1;
+        }
+    }
+    return counts;
+}
+
+// Calls count(u, row) for the nodes u of the window rows below n_nodes, chunks of nodes going to
+// the threads in turn.
+fn for_rows<F>(window: &Window, n_nodes: usize, n_thread: usize, count: F)
+    where F: Fn(usize, usize) + Sync{
+    let n_thread = n_thread.max(1);
+    let rows = window.rows.start.min(n_nodes)..window.rows.end.min(n_nodes);
+    let count = &count;
+    crossbeam::scope(|scope| {
+        for t in 0..n_thread {
+            let rows = rows.clone();
+            scope.spawn(move |_| {
+                for start in (rows.start + t * NODE_CHUNK..rows.end).step_by(NODE_CHUNK * n_thread) {
+                    for u in start..(start + NODE_CHUNK).min(rows.end) {
+                        count(u, window.row_cell(u).unwrap());
+                    }
+                }
+            });
+        }
+    }).unwrap();
+}
+
+pub fn scaling_csr(graph: &CSR, window: &Window, n_thread: usize) -> Vec<usize>{
+    let counts = atomic_counts(window);
+    for_rows(window, graph.n_nodes(), n_thread, |u, y| {
+        let row = &counts[y * window.col_pixels..(y + 1) * window.col_pixels];
+        for v in graph.neighbors(u) {
+            if let Some(x) = window.col_cell(*v) {
+                row[x].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    });
+    return into_counts(counts);
+}
+
+// A block of 64 columns falling in a single cell is counted at once.
+pub fn scaling_csbv(graph: &CSBV, window: &Window, n_thread: usize) -> Vec<usize>{
+    const BLOCK_SIZE: usize = 64;
+
+    let counts = atomic_counts(window);
+    for_rows(window, graph.n_nodes(), n_thread, |u, y| {
+        let row = &counts[y * window.col_pixels..(y + 1) * window.col_pixels];
+        for (block_id, mut bits) in graph.block_iter(u) {
+            if bits == 0 {
+                continue;
+            }
+            let base = block_id * BLOCK_SIZE;
+            let first = window.col_cell(base + bits.trailing_zeros() as usize);
+            let last = window.col_cell(base + BLOCK_SIZE - 1 - bits.leading_zeros() as usize);
+            if let (Some(x), true) = (first, first == last) {
+                row[x].fetch_add(bits.count_ones() as usize, Ordering::Relaxed);
+                continue;
+            }
+            while bits != 0 {
+                let offset = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                if let Some(x) = window.col_cell(base + offset) {
+                    row[x].fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    });
+    return into_counts(counts);
+}
+
+// Counts the edges of a text edge list, one "u v" pair per line, further columns (e.g. weights)
+// being ignored, as well as empty lines and comments starting with '#' or '%'. The file is read
+// by pieces, the lines of each piece being parsed by n_thread threads.
+pub fn scaling_edge_list(filepath: &str, window: &Window, n_thread: usize) -> Result<Vec<usize>>{
+    // bytes read at a time
+    const BUFFER_SIZE: usize = 1 << 24;
+
+    let n_thread = n_thread.max(1);
+    let counts = atomic_counts(window);
+    let mut file = File::open(filepath)?;
+    let mut buffer = vec![];
+    let mut eof = false;
+    while !eof {
+        // complete lines go to the threads, a partial last line waits for the next piece
+        let kept = buffer.len();
+        buffer.resize(kept + BUFFER_SIZE, 0);
+        let n_read = file.read(&mut buffer[kept..])?;
+        buffer.truncate(kept + n_read);
+        eof = n_read == 0;
+        let end = if eof { buffer.len() } else {
+            match buffer.iter().rposition(|b| *b == b'\n') {
+                Some(i) => i + 1,
+                None => continue
+            }
+        };
+
+        let results : Vec<Result<()>> = crossbeam::scope(|scope| {
+            let handles : Vec<_> = split_lines(&buffer[..end], n_thread).into_iter().map(|piece| {
+                let counts = &counts;
+                scope.spawn(move |_| count_lines(piece, window, counts))
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        }).unwrap();
+        for result in results {
+            result?;
+        }
+        buffer.drain(..end);
+    }
+    return Ok(into_counts(counts));
+}
+
+// about n_parts pieces of whole lines.
+fn split_lines(data: &[u8], n_parts: usize) -> Vec<&[u8]>{
+    let mut pieces = vec![];
+    let mut start = 0usize;
+    for t in 1..=n_parts {
+        let mut end = (data.len() * t / n_parts).max(start + 1).min(data.len());
+        while end < data.len() && data[end - 1] != b'\n' {
+            end += 1;
+        }
+        if end > start {
+            pieces.push(&data[start..end]);
+            start = end;
+        }
+    }
+    return pieces;
+}
+
+fn count_lines(piece: &[u8], window: &Window, counts: &[AtomicUsize]) -> Result<()>{
+    let invalid = |line: &str| Error::new(ErrorKind::InvalidData, format!("invalid edge line: {:?}", line));
+    let text = std::str::from_utf8(piece).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('%') {
+            continue;
+        }
+        let mut fields = line.split_whitespace().map(|x| x.parse::<usize>());
+        let (u, v) = match (fields.next(), fields.next()) {
+            (Some(Ok(u)), Some(Ok(v))) => (u, v),
+            _ => return Err(invalid(line))
+        };
+        if let Some((y, x)) = window.cell(u, v) {
+            counts[y * window.col_pixels + x].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    return Ok(());
+}
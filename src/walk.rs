@@ -0,0 +1,206 @@
+use std::fs::File;
+use std::io::{Result, BufWriter, Write};
+use std::ops::Range;
+
+use crossbeam;
+
+use crate::csr::CSR;
+use crate::rng::Rng;
+use crate::wcsr::WCSR;
+
+// Random walk corpora, e.g. to train node embeddings (DeepWalk, node2vec).
+// walks_per_node walks of walk_length nodes start from every node; walk i starts from node
+// i % n_nodes. Each walk draws from its own generator, seeded from the seed and i, so walks do
+// not depend on the number of threads. Walks stop early at nodes without out-neighbors.
+
+pub enum Walker<'a>{
+    // uniform neighbor at every step
+    Uniform(&'a CSR),
+    // neighbor with probability proportional to the edge weight
+    Weighted(&'a WCSR),
+    // second order walk of node2vec (Grover & Leskovec): coming from t, the next node x is
+    // weighted by 1/p if x = t, 1 if x is a neighbor of t and 1/q otherwise.
+    // Neighbor lists must be sorted (see `CSR::sort_neighbors`).
+    Node2Vec{ graph: &'a CSR, p: f64, q: f64 }
+}
+
+impl Walker<'_>{
+
+    fn n_nodes(&self) -> usize{
+        match self {
+            Walker::Uniform(graph) => graph.n_nodes(),
+            Walker::Weighted(graph) => graph.n_nodes(),
+            Walker::Node2Vec{ graph, .. } => graph.n_nodes()
+        }
+    }
+}
+
+// Calls f with the id and the nodes of every walk, from n_thread threads in no particular order.
+pub fn walks<F: Fn(usize, &[usize]) + Sync>(walker: &Walker, walk_length: usize, walks_per_node: usize, seed: u64,
+                                             n_thread: usize, f: F){
+    let n_thread = n_thread.max(1);
+    let n_walks = walker.n_nodes() * walks_per_node;
+    let cum_weights = cumulative_weights(walker);
+
+    let f = &f;
+    crossbeam::scope(|scope| {
+        for i in 0..n_thread {
+            let cum_weights = &cum_weights;
+            scope.spawn(move |_| {
+                let mut walk = Vec::with_capacity(walk_length);
+                for id in (i..n_walks).step_by(n_thread) {
+                    run(walker, cum_weights, id, walk_length, seed, &mut walk);
+                    f(id, &walk);
+                }
+            });
+        }
+    }).unwrap();
+}
+
+// Writes one walk per line, as node ids separated by spaces, in the order of the walk ids.
+pub fn write_walks(walker: &Walker, walk_length: usize, walks_per_node: usize, seed: u64, n_thread: usize,
+                   filepath: &str) -> Result<()>{
+    // walks of each thread per batch
+    const BATCH_SIZE: usize = 4096;
+
+    let n_thread = n_thread.max(1);
+    let n_walks = walker.n_nodes() * walks_per_node;
+    let cum_weights = cumulative_weights(walker);
+    let mut bw = BufWriter::new(File::create(filepath)?);
+
+    // threads format consecutive ranges of walks, written in order
+    let mut buffers = vec![vec![]; n_thread];
+    for start in (0..n_walks).step_by(BATCH_SIZE * n_thread) {
+        crossbeam::scope(|scope| {
+            for (i, buffer) in buffers.iter_mut().enumerate() {
+                let cum_weights = &cum_weights;
+                let first = (start + i * BATCH_SIZE).min(n_walks);
+                let ids : Range<usize> = first..(first + BATCH_SIZE).min(n_walks);
+                scope.spawn(move |_| {
+                    buffer.clear();
+                    let mut walk = Vec::with_capacity(walk_length);
+                    for id in ids {
+                        run(walker, cum_weights, id, walk_length, seed, &mut walk);
+                        format_walk(&walk, buffer);
+                    }
+                });
+            }
+        }).unwrap();
+
+        for buffer in &buffers {
+            bw.write_all(buffer)?;
+        }
+    }
+
+    bw.flush()?;
+
+    return Ok(());
+}
+
+fn format_walk(walk: &[usize], buffer: &mut Vec<u8>){
+    for (i, u) in walk.iter().enumerate() {
+        if i > 0 {
+            buffer.push(b' ');
+        }
+        buffer.extend_from_slice(u.to_string().as_bytes());
+    }
+    buffer.push(b'\n');
+}
+
+// Running sums of the weights of each neighbor list, aligned with edges, for weighted walks.
+fn cumulative_weights(walker: &Walker) -> Vec<f64>{
+    let graph = match walker {
+        Walker::Weighted(graph) => graph,
+        _ => return vec![]
+    };
+
+    let mut cum_weights = vec![0f64; graph.edges.len()];
+    for u in 0..graph.n_nodes() {
+        let mut sum = 0f64;
+        for p in graph.nodes[u]..graph.nodes[u+1] {
+            sum += graph.weights[p];
+            cum_weights[p] = sum;
+        }
+    }
+    return cum_weights;
+}
+
+fn walk_rng(seed: u64, id: usize) -> Rng{
+    let mut hash = Rng::new(id as u64);
+    return Rng::new(seed ^ hash.next_u64());
+}
+
+fn run(walker: &Walker, cum_weights: &[f64], id: usize, walk_length: usize, seed: u64, walk: &mut Vec<usize>){
+    let mut rng = walk_rng(seed, id);
+    walk.clear();
+    if walk_length == 0 {
+        return;
+    }
+    walk.push(id % walker.n_nodes());
+
+    while walk.len() < walk_length {
+        let u = walk[walk.len() - 1];
+        let next = match walker {
+            Walker::Uniform(graph) => uniform_step(graph, u, &mut rng),
+            Walker::Weighted(graph) => weighted_step(graph, cum_weights, u, &mut rng),
+            Walker::Node2Vec{ graph, p, q } => {
+                if walk.len() == 1 {
+                    uniform_step(graph, u, &mut rng)
+                }
+                else {
+                    node2vec_step(graph, walk[walk.len() - 2], u, *p, *q, &mut rng)
+                }
+            }
+        };
+
+        match next {
+            Some(v) => walk.push(v),
+            None => break
+        }
+    }
+}
+
+fn uniform_step(graph: &CSR, u: usize, rng: &mut Rng) -> Option<usize>{
+    if graph.degree(u) == 0 {
+        return None;
+    }
+    return Some(graph.neighbors(u)[rng.gen_range(graph.degree(u))]);
+}
+
+fn weighted_step(graph: &WCSR, cum_weights: &[f64], u: usize, rng: &mut Rng) -> Option<usize>{
+    let cum = &cum_weights[graph.nodes[u]..graph.nodes[u+1]];
+    let total = *cum.last()?;
+    if total <= 0.0 {
+        return None;
+    }
+
+    let r = rng.gen_f64() * total;
+    let i = cum.partition_point(|x| *x <= r).min(cum.len() - 1);
+    return Some(graph.neighbors(u)[i]);
+}
+
+// Rejection sampling (Yang et al., KnightKing): a uniform neighbor is accepted with probability
+// its weight over the largest weight, which avoids precomputing the transition tables of every edge.
+fn node2vec_step(graph: &CSR, t: usize, u: usize, p: f64, q: f64, rng: &mut Rng) -> Option<usize>{
+    if graph.degree(u) == 0 {
+        return None;
+    }
+
+    let max_weight = (1.0 / p).max(1.0).max(1.0 / q);
+    loop {
+        let x = graph.neighbors(u)[rng.gen_range(graph.degree(u))];
+        let weight = if x == t {
+            1.0 / p
+        }
+        else if graph.neighbors(t).binary_search(&x).is_ok() {
+            1.0
+        }
+        else {
+            1.0 / q
+        };
+
+        if rng.gen_f64() * max_weight < weight {
+            return Some(x);
+        }
+    }
+}
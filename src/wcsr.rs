@@ -0,0 +1,89 @@
+use crate::csr::CSR;
+
+// CSR with a weight for every edge, stored in weights[] aligned with edges[].
+pub struct WCSR{
+    pub nodes: Vec<usize>,
+    pub edges: Vec<usize>,
+    pub weights: Vec<f64>
+}
+
+impl WCSR{
+
+    pub fn from_sorted_edges(edges: &[(usize, usize, f64)], n_nodes: usize) -> WCSR{
+        let n_edges = edges.len();
+
+        let mut wcsr = WCSR{
+            nodes: vec![0; n_nodes + 1],
+            edges: vec![0; n_edges],
+            weights: vec![0.0; n_edges]
+        };
+
+        for (i, (u, v, w)) in edges.iter().enumerate() {
+            wcsr.edges[i] = *v;
+            wcsr.weights[i] = *w;
+            wcsr.nodes[*u + 1] += 1;
+        }
+
+        for i in 1..n_nodes {
+            wcsr.nodes[i+1] += wcsr.nodes[i];
+        }
+
+        return wcsr;
+    }
+
+    // stores every edge in both directions.
+    pub fn from_edges(edges: &[(usize, usize, f64)], n_nodes: usize) -> WCSR{
+        let n_edges = edges.len();
+        let mut wcsr = WCSR{
+            nodes: vec![0; n_nodes + 1],
+            edges: vec![0; n_edges*2],
+            weights: vec![0.0; n_edges*2]
+        };
+
+        for (u, v, _) in edges {
+            wcsr.nodes[*u+1] += 1;
+            wcsr.nodes[*v+1] += 1;
+        }
+
+        for i in 0..n_nodes {
+            wcsr.nodes[i+1] += wcsr.nodes[i];
+        }
+
+        let mut ptrs = wcsr.nodes.clone();
+        for (u, v, w) in edges {
+            wcsr.edges[ptrs[*u]] = *v;
+            wcsr.weights[ptrs[*u]] = *w;
+            ptrs[*u] += 1;
+            wcsr.edges[ptrs[*v]] = *u;
+            wcsr.weights[ptrs[*v]] = *w;
+            ptrs[*v] += 1;
+        }
+
+        return wcsr;
+    }
+
+    // every edge gets weight 1.
+    pub fn from_csr(graph: &CSR) -> WCSR{
+        return WCSR{
+            nodes: graph.nodes.clone(),
+            edges: graph.edges.clone(),
+            weights: vec![1.0; graph.edges.len()]
+        };
+    }
+
+    pub fn degree(&self, u: usize) -> usize {
+        return self.nodes[u+1] - self.nodes[u];
+    }
+
+    pub fn neighbors(&self, u: usize) -> &[usize] {
+        return &self.edges[self.nodes[u]..self.nodes[u+1]];
+    }
+
+    pub fn weights(&self, u: usize) -> &[f64] {
+        return &self.weights[self.nodes[u]..self.nodes[u+1]];
+    }
+
+    pub fn n_nodes(&self) -> usize{
+        return self.nodes.len() - 1;
+    }
+}